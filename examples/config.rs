@@ -6,12 +6,10 @@ use nacos_api::model::DeployConfig;
 async fn main() {
     let client = NacosConfigClient::from(test_config_api());
     let nacos_config = test_nacos_config();
-    client.listen_config(
-        &nacos_config,
-        |s| { println!(" perceive the configs changed to > {}", s) },
-        10
-    ).await;
-    loop {}
+    let mut changes = client.watch(&nacos_config);
+    while let Some(change) = changes.recv().await {
+        println!(" perceive the configs changed to > {}", change.new_content)
+    }
 }
 
 fn test_nacos_config() -> NacosConfig {
@@ -30,7 +28,17 @@ fn test_deploy_config() -> DeployConfig {
 #[cfg(test)]
 mod config_test {
     use crate::{test_nacos_config, test_deploy_config, test_config_api};
-    use nacos_api::{NacosConfig, NacosConfigApi};
+    use nacos_api::{NacosConfig, NacosConfigApi, NacosConfigClient};
+    use nacos_api::model::service_dto::SearchMode;
+    use nacos_api::integration::composite::{CompositeConfig, application_layers};
+    use nacos_api::integration::binding::NacosConfig;
+    use nacos_api::LiveConfig;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, NacosConfig)]
+    #[serde(transparent)]
+    #[nacos(data_id = "test_data", group = "test_grep", format = "text", refresh = false)]
+    struct TestData(String);
 
     #[tokio::test]
     async fn test_upload_configs() {
@@ -52,6 +60,36 @@ mod config_test {
         println!("{:?}", result);
     }
 
+    #[tokio::test]
+    async fn test_get_history() {
+        let nacos: NacosConfig = test_nacos_config();
+        let result = test_config_api().get_history(&nacos, 1, 10).await;
+        println!("{:?}", result);
+    }
+
+    #[tokio::test]
+    async fn test_search_configs() {
+        let nacos: NacosConfig = test_nacos_config();
+        let result = NacosConfigApi::search(&nacos, SearchMode::Accurate, 1, 10, None).await;
+        println!("{:?}", result);
+    }
+
+    #[tokio::test]
+    async fn test_composite_configs() {
+        let nacos: NacosConfig = test_nacos_config();
+        let mut layers = vec![NacosConfigClient::from(NacosConfigApi::new(test_deploy_config()))];
+        layers.extend(application_layers("test_app", Some("dev"), "yaml", "test_grep", None));
+        let result = CompositeConfig::from_clients(layers).get(&nacos).await;
+        println!("{:?}", result);
+    }
+
+    #[tokio::test]
+    async fn test_bind_configs() {
+        let nacos: NacosConfig = test_nacos_config();
+        let result = LiveConfig::<TestData>::bind(&nacos).await.map(|data| data.load());
+        println!("{:?}", result);
+    }
+
     #[tokio::test]
     async fn test_delete_configs() {
         let nacos: NacosConfig = test_nacos_config();
@@ -3,8 +3,8 @@ use nacos_api::{NacosClient, NacosConfig, ServerConfig};
 #[tokio::main]
 async fn main() {
     let client = test_client();
-    client.register(&None).await;
-    loop {}
+    client.register(None).await;
+    tokio::signal::ctrl_c().await.unwrap();
 }
 
 fn test_nacos_config() -> NacosConfig {
@@ -18,6 +18,8 @@ fn test_client() -> NacosClient {
             "127.0.0.1",
             8080,
             "test",
+            None,
+            None,
         ),
     )
 }
@@ -39,17 +41,30 @@ mod server_test {
             test_client().nacos_config(),
             "test", "127.0.0.1",
             8080,
-            &None,
+            None,
         ).await;
         println!(" -- > instance : {:?}", instance);
     }
 
+    #[tokio::test]
+    async fn test_update_instance_health() {
+        let client = test_client();
+        let result = client.service_api().update_instance_health(
+            client.nacos_config(),
+            true,
+            None,
+        ).await;
+        println!(" -- > update health : {:?}", result);
+    }
+
     #[tokio::test]
     async fn test_get_server() {
         let server = NacosServiceApi::get_server(
             test_client().nacos_config(),
             "test",
-            &None,
+            None,
+            None,
+            None,
         ).await;
         println!(" -- > server : {:?}", server);
     }
@@ -60,7 +75,7 @@ mod server_test {
             test_client().nacos_config(),
             1,
             10,
-            &None,
+            None,
         ).await;
         println!(" -- > server_list : {:?}", server_list);
     }
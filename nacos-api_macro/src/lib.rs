@@ -1,36 +1,233 @@
 use proc_macro::TokenStream;
-use syn::{DeriveInput, Data, DataStruct, Fields};
+use proc_macro2::TokenStream as TokenStream2;
+use syn::{DeriveInput, Data, DataStruct, Fields, Field, GenericArgument, Lit, Meta, NestedMeta, Path, PathArguments, Type};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
 use quote::quote;
 use heck::MixedCase;
 
-#[proc_macro_derive(Dto)]
+/// 为请求参数结构体实现 `nacos_api::model::Dto` , 字段名转为 mixedCase 作为参数名 ;
+/// `Option` 字段为 None 时不传 , 其他字段总是传递 , 字段属性 :
+/// - `#[dto(rename = "...")]` 指定参数名
+/// - `#[dto(skip)]` 不作为参数
+/// - `#[dto(flatten)]` 展开嵌套的 Dto 结构体
+/// - `#[dto(with = "path")]` 以 `fn(&T) -> String` 代替 `to_string()` , `Option<T>` 字段传入 `&T`
+#[proc_macro_derive(Dto, attributes(dto))]
 pub fn dto_derive(input: TokenStream) -> TokenStream {
-    let ast: DeriveInput = syn::parse(input)
-        .expect(" -- parse err at derive dto ");
+    let ast: DeriveInput = match syn::parse(input) {
+        Ok(ast) => ast,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    match expand(&ast) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// 为可选项结构体生成链式构造器 `X::builder().field(value).build()` , 结构体需要实现 `Default` ;
+/// `Option<T>` 字段的方法接收 `impl Into<T>` , 构造器可以直接作为 `impl Into<Option<X>>` 参数传递 ;
+/// 方法名默认为字段名 , `#[builder(rename = "...")]` 指定方法名
+#[proc_macro_derive(Builder, attributes(builder))]
+pub fn builder_derive(input: TokenStream) -> TokenStream {
+    let ast: DeriveInput = match syn::parse(input) {
+        Ok(ast) => ast,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    match expand_builder(&ast) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
 
-    let name = &ast.ident.clone();
+fn expand_builder(ast: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &ast.ident;
+    let vis = &ast.vis;
     let fields = match &ast.data {
-        Data::Struct(DataStruct {
-                         fields: Fields::Named(fields),
-                         ..
-                     }) => &fields.named,
-        _ => panic!(" -- analysis fields err at derive dto "),
+        Data::Struct(DataStruct { fields: Fields::Named(fields), .. }) => &fields.named,
+        _ => return Err(syn::Error::new_spanned(name, "Builder can only be derived for structs with named fields")),
     };
+    if !ast.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(&ast.generics, "Builder can not be derived for generic structs"));
+    }
+    let builder = syn::Ident::new(&format!("{}Builder", name), name.span());
+    let builder_doc = format!("[{}] 的链式构造器", name);
 
-    let itm = fields.iter().map(|field| {
-        let field_name = field.ident.clone().unwrap();
-        let mix = field_name.to_string().to_mixed_case();
+    let methods = fields.iter().map(builder_method).collect::<syn::Result<Vec<_>>>()?;
 
-        quote! {
-            if let Some(s) = &self.#field_name {
-                map.insert(#mix.to_string() , s.to_string());
+    Ok(quote! {
+        #[doc = #builder_doc]
+        #[derive(Default)]
+        #vis struct #builder {
+            inner: #name,
+        }
+
+        impl #name {
+            pub fn builder() -> #builder {
+                #builder::default()
             }
         }
-    });
 
-    TokenStream::from(quote! {
-        impl Dto for #name {
-            fn mapping(&self, map: &mut HashMap<String, String>) {
+        impl #builder {
+            #(
+                #methods
+            )*
+
+            pub fn build(self) -> #name {
+                self.inner
+            }
+        }
+
+        impl ::std::convert::From<#builder> for ::std::option::Option<#name> {
+            fn from(builder: #builder) -> Self {
+                ::std::option::Option::Some(builder.build())
+            }
+        }
+    })
+}
+
+fn builder_method(field: &Field) -> syn::Result<TokenStream2> {
+    let field_name = field.ident.clone().unwrap();
+    let method = builder_rename(field)?.unwrap_or_else(|| field_name.clone());
+    let docs = field.attrs.iter().filter(|a| a.path.is_ident("doc"));
+    Ok(match option_inner(&field.ty) {
+        Some(inner) => quote! {
+            #(#docs)*
+            pub fn #method(mut self, value: impl ::std::convert::Into<#inner>) -> Self {
+                self.inner.#field_name = ::std::option::Option::Some(value.into());
+                self
+            }
+        },
+        None => {
+            let ty = &field.ty;
+            quote! {
+                #(#docs)*
+                pub fn #method(mut self, value: impl ::std::convert::Into<#ty>) -> Self {
+                    self.inner.#field_name = value.into();
+                    self
+                }
+            }
+        }
+    })
+}
+
+/// 读取字段上的 `#[builder(rename = "...")]`
+fn builder_rename(field: &Field) -> syn::Result<Option<syn::Ident>> {
+    let mut rename = None;
+    for attr in field.attrs.iter().filter(|a| a.path.is_ident("builder")) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            other => return Err(syn::Error::new_spanned(other, "expected `#[builder(...)]`")),
+        };
+        for nested in list.nested {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("rename") => match nv.lit {
+                    Lit::Str(s) => rename = Some(s.parse()?),
+                    lit => return Err(syn::Error::new_spanned(lit, "expected a string for `rename`")),
+                },
+                other => return Err(syn::Error::new(other.span(), "unsupported builder attribute , expected `rename = \"...\"`")),
+            }
+        }
+    }
+    Ok(rename)
+}
+
+/// 将结构体绑定到 nacos 配置 , 实现 `nacos_api::integration::binding::NacosConfigBinding` ,
+/// 由 `LiveConfig::<T>::bind` 加载 ; 结构体需要实现 `Deserialize` , 结构体属性 :
+/// - `data_id = "..."` 必填
+/// - `group = "..."` 默认为 DEFAULT_GROUP
+/// - `tenant = "..."` 命名空间ID , 默认为 public
+/// - `format = "..."` json yaml toml properties 或 text , 默认按配置 type 或 dataId 扩展名识别
+/// - `refresh = bool` 是否随配置变更刷新 , 默认为 true
+#[proc_macro_derive(NacosConfig, attributes(nacos))]
+pub fn nacos_config_derive(input: TokenStream) -> TokenStream {
+    let ast: DeriveInput = match syn::parse(input) {
+        Ok(ast) => ast,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    match expand_binding(&ast) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand_binding(ast: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &ast.ident;
+    let mut data_id = None;
+    let mut group = "DEFAULT_GROUP".to_string();
+    let mut tenant = quote! { ::std::option::Option::None };
+    let mut format = quote! { ::std::option::Option::None };
+    let mut refresh = true;
+    for attr in ast.attrs.iter().filter(|a| a.path.is_ident("nacos")) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            other => return Err(syn::Error::new_spanned(other, "expected `#[nacos(...)]`")),
+        };
+        for nested in list.nested {
+            let nv = match nested {
+                NestedMeta::Meta(Meta::NameValue(nv)) => nv,
+                other => return Err(syn::Error::new(other.span(), "expected `key = value` in `#[nacos(...)]`")),
+            };
+            let key = nv.path.get_ident().map(|i| i.to_string()).unwrap_or_default();
+            match (key.as_str(), &nv.lit) {
+                ("data_id", Lit::Str(s)) if !s.value().trim().is_empty() => data_id = Some(s.value()),
+                ("data_id", lit) => return Err(syn::Error::new_spanned(lit, "expected a non-empty string for `data_id`")),
+                ("group", Lit::Str(s)) => group = s.value(),
+                ("tenant", Lit::Str(s)) => {
+                    let value = s.value();
+                    tenant = quote! { ::std::option::Option::Some(#value) };
+                }
+                ("format", Lit::Str(s)) => {
+                    let variant = match s.value().trim().to_ascii_lowercase().as_str() {
+                        "json" => quote! { Json },
+                        "yaml" | "yml" => quote! { Yaml },
+                        "toml" => quote! { Toml },
+                        "properties" => quote! { Properties },
+                        "text" | "txt" => quote! { Text },
+                        _ => return Err(syn::Error::new_spanned(s, "unsupported format , expected json, yaml, toml, properties or text")),
+                    };
+                    format = quote! { ::std::option::Option::Some(::nacos_api::model::config_format::ConfigFormat::#variant) };
+                }
+                ("refresh", Lit::Bool(b)) => refresh = b.value,
+                ("group" | "tenant" | "format", lit) => return Err(syn::Error::new_spanned(lit, format!("expected a string for `{}`", key))),
+                ("refresh", lit) => return Err(syn::Error::new_spanned(lit, "expected a bool for `refresh`")),
+                _ => return Err(syn::Error::new_spanned(&nv.path,
+                    "unsupported nacos attribute , expected `data_id`, `group`, `tenant`, `format` or `refresh`")),
+            }
+        }
+    }
+    let data_id = data_id.ok_or_else(|| syn::Error::new_spanned(name, "missing `#[nacos(data_id = \"...\")]`"))?;
+
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::nacos_api::integration::binding::NacosConfigBinding for #name #ty_generics #where_clause {
+            fn binding() -> ::nacos_api::integration::binding::ConfigBinding {
+                ::nacos_api::integration::binding::ConfigBinding {
+                    data_id: #data_id,
+                    group: #group,
+                    tenant: #tenant,
+                    format: #format,
+                    refresh: #refresh,
+                }
+            }
+        }
+    })
+}
+
+fn expand(ast: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &ast.ident;
+    let fields = match &ast.data {
+        Data::Struct(DataStruct { fields: Fields::Named(fields), .. }) => &fields.named,
+        _ => return Err(syn::Error::new_spanned(name, "Dto can only be derived for structs with named fields")),
+    };
+
+    let itm = fields.iter()
+        .map(field_mapping)
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::nacos_api::model::Dto for #name #ty_generics #where_clause {
+            fn mapping(&self, map: &mut ::std::collections::HashMap<::std::string::String, ::std::string::String>) {
                 #(
                     #itm
                 )*
@@ -39,3 +236,108 @@ pub fn dto_derive(input: TokenStream) -> TokenStream {
     })
 }
 
+fn field_mapping(field: &Field) -> syn::Result<TokenStream2> {
+    let attrs = DtoAttrs::parse(field)?;
+    let field_name = field.ident.clone().unwrap();
+    if attrs.skip { return Ok(quote! {}); }
+    let optional = is_option(&field.ty);
+
+    if attrs.flatten {
+        if let Some(span) = attrs.rename_span.or(attrs.with_span) {
+            return Err(syn::Error::new(span, "`flatten` can not be used with `rename` or `with`"));
+        }
+        return Ok(if optional {
+            quote! {
+                if let Some(s) = &self.#field_name {
+                    ::nacos_api::model::Dto::mapping(s, map);
+                }
+            }
+        } else {
+            quote! { ::nacos_api::model::Dto::mapping(&self.#field_name, map); }
+        });
+    }
+
+    let mix = attrs.rename.unwrap_or_else(|| field_name.unraw().to_string().to_mixed_case());
+    let with = attrs.with;
+    let value = |s: TokenStream2| match &with {
+        Some(with) => quote! { #with(#s) },
+        None => quote! { ::std::string::ToString::to_string(#s) },
+    };
+    Ok(if optional {
+        let value = value(quote! { s });
+        quote! {
+            if let Some(s) = &self.#field_name {
+                map.insert(#mix.to_string(), #value);
+            }
+        }
+    } else {
+        let value = value(quote! { &self.#field_name });
+        quote! { map.insert(#mix.to_string(), #value); }
+    })
+}
+
+#[derive(Default)]
+struct DtoAttrs {
+    rename: Option<String>,
+    rename_span: Option<proc_macro2::Span>,
+    skip: bool,
+    flatten: bool,
+    with: Option<Path>,
+    with_span: Option<proc_macro2::Span>,
+}
+
+impl DtoAttrs {
+    /// 读取字段上的 `#[dto(...)]`
+    fn parse(field: &Field) -> syn::Result<Self> {
+        let mut attrs = Self::default();
+        for attr in field.attrs.iter().filter(|a| a.path.is_ident("dto")) {
+            let list = match attr.parse_meta()? {
+                Meta::List(list) => list,
+                other => return Err(syn::Error::new_spanned(other, "expected `#[dto(...)]`")),
+            };
+            for nested in list.nested {
+                match nested {
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("rename") => match nv.lit {
+                        Lit::Str(s) => {
+                            attrs.rename_span = Some(s.span());
+                            attrs.rename = Some(s.value());
+                        }
+                        lit => return Err(syn::Error::new_spanned(lit, "expected a string for `rename`")),
+                    },
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("with") => match nv.lit {
+                        Lit::Str(s) => {
+                            attrs.with_span = Some(s.span());
+                            attrs.with = Some(s.parse()?);
+                        }
+                        lit => return Err(syn::Error::new_spanned(lit, "expected a function path string for `with`")),
+                    },
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip") => attrs.skip = true,
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("flatten") => attrs.flatten = true,
+                    other => return Err(syn::Error::new(other.span(),
+                        "unsupported dto attribute , expected `rename = \"...\"`, `skip`, `flatten` or `with = \"path\"`")),
+                }
+            }
+        }
+        Ok(attrs)
+    }
+}
+
+/// 字段类型是否为 `Option<_>`
+fn is_option(ty: &Type) -> bool {
+    option_inner(ty).is_some()
+}
+
+/// `Option<T>` 中的 `T`
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let seg = match ty {
+        Type::Path(tp) if tp.qself.is_none() => tp.path.segments.last()?,
+        _ => return None,
+    };
+    match &seg.arguments {
+        PathArguments::AngleBracketed(args) if seg.ident == "Option" && args.args.len() == 1 => match args.args.first() {
+            Some(GenericArgument::Type(inner)) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -1,49 +1,625 @@
 use crate::{NacosConfig, util};
+use crate::api::filter::{ConfigFilter, FilteredConfig, CIPHER_PREFIX};
 use crate::model::DeployConfig;
+use crate::model::config_vo::{BetaConfigInfo, ConfigContent, ConfigHistory, ConfigImportSummary, ConfigInfo, NacosPage, NacosResult};
+use crate::model::service_dto::{ExportConfigsOption, PostConfigsOption, SameConfigPolicy, SearchConfigsOption, SearchMode};
+use serde_json::json;
+use std::collections::VecDeque;
+use crate::model::err::{NacosError, NacosErrorKind};
+use crate::util::transport::{HttpRequest, HttpResponse};
+use std::collections::HashMap;
 use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
 
 const GET_CONFIGS: &str = "/v1/cs/configs";
 const POST_CONFIGS: &str = "/v1/cs/configs";
 const DELETE_CONFIGS: &str = "/v1/cs/configs";
+const POST_CONFIGS_LISTENER: &str = "/v1/cs/configs/listener";
+const GET_CONFIGS_SEARCH: &str = "/v1/cs/configs";
+const GET_CONFIGS_EXPORT: &str = "/v1/cs/configs";
+const POST_CONFIGS_IMPORT: &str = "/v1/cs/configs";
+const POST_CONFIGS_CLONE: &str = "/v1/cs/configs";
+const GET_HISTORY: &str = "/v1/cs/history";
+const GET_HISTORY_PREVIOUS: &str = "/v1/cs/history/previous";
+const CONFIG_TYPE_HEADER: &str = "Config-Type";
+const BETA_IPS_HEADER: &str = "betaIps";
+const ENCRYPTED_DATA_KEY_HEADER: &str = "Encrypted-Data-Key";
+
+/// 长轮询监听的默认超时时间 毫秒
+pub const LONG_POLLING_TIMEOUT: u64 = 30000;
 
 #[derive(Clone)]
 pub struct NacosConfigApi {
     deploy_config: DeployConfig,
+    filters: Vec<Arc<dyn ConfigFilter>>,
 }
 
 impl NacosConfigApi {
     pub fn new(config: DeployConfig) -> Self {
         Self {
             deploy_config: config,
+            filters: Vec::new(),
         }
     }
     pub fn deploy_config(&self) -> &DeployConfig {
         &self.deploy_config
     }
+
+    /// 加入配置过滤器 , 发布前按加入顺序执行 , 获取后按逆序执行
+    /// add a config filter , which runs in the added order before publish and in reverse order after fetch .
+    /// ```rust,no_run
+    /// use nacos_api::{NacosConfigApi, NacosConfig, DeployConfig};
+    /// use nacos_api::api::filter::AesConfigFilter;
+    /// use std::sync::Arc;
+    ///
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let nacos = NacosConfig::new("http", "192.168.0.132", 8848);
+    /// let api = NacosConfigApi::new(DeployConfig::new("cipher-aes-db.yaml", "DEFAULT_GROUP", None))
+    ///     .with_filter(Arc::new(AesConfigFilter::new("0123456789abcdef")?));
+    /// // stored encrypted in server
+    /// api.publish_configs(&nacos, "password: secret", Some("yaml".to_string()), None).await?;
+    /// // decrypted when fetched
+    /// assert_eq!(api.get_configs(&nacos).await?, "password: secret");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_filter(mut self, filter: Arc<dyn ConfigFilter>) -> Self {
+        self.add_filter(filter);
+        self
+    }
+    pub fn add_filter(&mut self, filter: Arc<dyn ConfigFilter>) {
+        self.filters.push(filter);
+    }
+    pub fn filters(&self) -> &[Arc<dyn ConfigFilter>] {
+        &self.filters
+    }
+
+    fn filtered(&self, content: String, encrypted_data_key: Option<String>) -> FilteredConfig {
+        let dc = &self.deploy_config;
+        FilteredConfig {
+            data_id: dc.data_id().to_string(),
+            group: dc.group().to_string(),
+            tenant: dc.tenant().clone(),
+            content,
+            encrypted_data_key,
+        }
+    }
 }
 
 impl NacosConfigApi {
     pub async fn get_configs(&self, nacos: &NacosConfig)
                              -> Result<String, Box<dyn Error>> {
+        Ok(self.get_configs_content(nacos).await?.content)
+    }
+
+    /// 获取配置内容及其 type , 内容经过已加入的过滤器处理
+    /// get the config content with its type , the content is handled by the added filters .
+    pub async fn get_configs_content(&self, nacos: &NacosConfig)
+                                     -> Result<ConfigContent, Box<dyn Error>> {
+        let (mut config, config_type) = self.fetch_raw(nacos).await?;
+        let md5 = format!("{:x}", md5::compute(&config.content));
+        for filter in self.filters.iter().rev() {
+            filter.after_fetch(&mut config)?;
+        }
+        Ok(ConfigContent { content: config.content, config_type, md5 })
+    }
+
+    /// 获取服务端保存的内容 , 不经过过滤器
+    async fn fetch_raw(&self, nacos: &NacosConfig)
+                       -> Result<(FilteredConfig, Option<String>), Box<dyn Error>> {
         let map = self.deploy_config.init_map();
-        let resp = util::query(&map, |c| c.get(nacos.addr(GET_CONFIGS))).await?;
-        let result = resp.text().await?;
-        Ok(result)
+        let resp = util::query(nacos, HttpRequest::get(&nacos.addr(GET_CONFIGS)), &map).await?;
+        if resp.status == 404 {
+            return Err(Box::new(NacosError::new(NacosErrorKind::NotFound, "config data not exist")));
+        }
+        let config_type = resp.header(CONFIG_TYPE_HEADER).map(|v| v.to_string());
+        let encrypted_data_key = resp.header(ENCRYPTED_DATA_KEY_HEADER).map(|v| v.to_string());
+        let result = resp.text();
+        if !resp.is_success() { return Err(Box::new(NacosError::throw(&result))); }
+        Ok((self.filtered(result, encrypted_data_key), config_type))
+    }
+
+    /// 长轮询监听配置 , 在 [timeout_ms] 毫秒内服务端配置的 md5 与 [content_md5] 不一致时返回 true ,
+    /// 超时仍未改变则返回 false
+    /// long polling listen the config , return true if the md5 of the config in server is not [content_md5]
+    /// within [timeout_ms] millis , or false when timeout .
+    pub async fn listen_configs(&self, nacos: &NacosConfig, content_md5: &str, timeout_ms: u64)
+                                -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let dc = &self.deploy_config;
+        let mut listening = format!("{}\u{2}{}\u{2}{}", dc.data_id(), dc.group(), content_md5);
+        if let Some(tenant) = dc.tenant() {
+            listening.push('\u{2}');
+            listening.push_str(tenant);
+        }
+        listening.push('\u{1}');
+        let mut map = HashMap::<String, String>::new();
+        map.insert("Listening-Configs".to_string(), listening);
+        let request = HttpRequest::post(&nacos.addr(POST_CONFIGS_LISTENER))
+            .header("Long-Pulling-Timeout", &timeout_ms.to_string())
+            .form(&map)
+            .timeout(Duration::from_millis(timeout_ms) + Duration::from_secs(10));
+        let resp = nacos.transport().send(request).await?;
+        let result = resp.text();
+        if !resp.is_success() { return Err(Box::new(NacosError::throw(&result))); }
+        Ok(!result.trim().is_empty())
     }
 
     pub async fn upload_configs(nacos: &NacosConfig, config: DeployConfig, content: &str, types: Option<String>)
                                 -> Result<(), Box<dyn Error>> {
-        let mut map = config.init_map();
-        map.insert("content".to_string(), content.to_string());
+        Self::upload_configs_with(nacos, config, content, types, None).await
+    }
+
+    /// 发布配置并附带应用名 发布人 标签 描述等信息 ; 静态发布不经过过滤器 ,
+    /// `cipher-` 前缀的 dataId 会被拒绝 , 需要通过 [with_filter] 加入加密过滤器后发布
+    /// publish the config with the app name , publisher , tags , description and so on .
+    /// the static publish has no filter , so the dataId prefixed by `cipher-` is refused ,
+    /// publish it after adding an encryption filter by [with_filter] .
+    /// ```rust,no_run
+    /// use nacos_api::{NacosConfigApi, NacosConfig, DeployConfig};
+    /// use nacos_api::model::service_dto::PostConfigsOption;
+    ///
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let nacos = NacosConfig::new("http", "192.168.0.132", 8848);
+    /// let option = PostConfigsOption::builder()
+    ///     .app_name("orders")
+    ///     .src_user("deploy-pipeline")
+    ///     .desc("release 1.2.0");
+    /// NacosConfigApi::upload_configs_with(
+    ///     &nacos,
+    ///     DeployConfig::new("orders.yaml", "DEFAULT_GROUP", None),
+    ///     "max_items: 10",
+    ///     Some("yaml".to_string()),
+    ///     option,
+    /// ).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn upload_configs_with(nacos: &NacosConfig, config: DeployConfig, content: &str, types: Option<String>,
+                                     option: impl Into<Option<PostConfigsOption>>)
+                                     -> Result<(), Box<dyn Error>> {
+        let option = option.into();
+        Self::new(config).publish_configs(nacos, content, types, option).await
+    }
+
+    /// 经过已加入的过滤器发布当前配置
+    /// publish current config through the added filters .
+    pub async fn publish_configs(&self, nacos: &NacosConfig, content: &str, types: Option<String>,
+                                 option: impl Into<Option<PostConfigsOption>>)
+                                 -> Result<(), Box<dyn Error>> {
+        let option = option.into();
+        let map = self.publish_map(content, types)?;
+        let resp = util::query_resp(nacos, HttpRequest::post(&nacos.addr(POST_CONFIGS)), map, &option).await?;
+        util::resp_assert(resp, "true")
+    }
+
+    fn publish_map(&self, content: &str, types: Option<String>) -> Result<HashMap<String, String>, NacosError> {
+        let mut config = self.filtered(content.to_string(), None);
+        for filter in self.filters.iter() {
+            filter.before_publish(&mut config)?;
+        }
+        // 没有过滤器加密的加密配置会以明文保存
+        if config.data_id.starts_with(CIPHER_PREFIX) && config.encrypted_data_key.is_none() {
+            let reason = format!("cipher dataId {} must be published through an encryption filter", config.data_id);
+            return Err(NacosError::new(NacosErrorKind::Validate, &reason));
+        }
+        let mut map = self.deploy_config.init_map();
+        map.insert("content".to_string(), config.content);
+        if let Some(key) = config.encrypted_data_key { map.insert("encryptedDataKey".to_string(), key); }
         if let Some(t) = types { map.insert("type".to_string(), t); }
-        let resp = util::query(&map, |c| c.post(nacos.addr(POST_CONFIGS))).await?;
-        util::resp_assert(resp, "true").await
+        Ok(map)
+    }
+
+    /// 比较并交换地发布配置 , 仅当服务端配置的 md5 等于 [cas_md5] 时写入 , 否则返回 Conflict 错误 ;
+    /// 注意 : 配置不存在时 nacos 忽略 [cas_md5] 直接新增 , 并发创建同一配置时都会成功 , 需要互斥创建时应预先创建该配置 .
+    /// 服务端对 cas 失败的响应因版本而异 , 写入失败后重新获取 md5 与 [cas_md5] 比较来判断冲突 ,
+    /// 这只是一种推测 : 期间配置又被改回时按服务端错误返回 , 获取失败时同样按服务端错误返回
+    /// publish the config with compare-and-swap , only written if the md5 of the config in server is [cas_md5] ,
+    /// or else return a Conflict err .
+    /// note : nacos ignores [cas_md5] and inserts the config if it is absent , so concurrent creators all succeed ,
+    /// create the config beforehand if the creation must be exclusive .
+    /// the response of a failed cas differs between nacos versions , so a Conflict is detected by refetching the md5
+    /// after the write failed and comparing it with [cas_md5] . it is a heuristic : if the config is changed back
+    /// meanwhile or the refetch fails , the server err is returned instead .
+    pub async fn upload_configs_cas(nacos: &NacosConfig, config: DeployConfig, content: &str, types: Option<String>,
+                                    cas_md5: &str, option: impl Into<Option<PostConfigsOption>>)
+                                    -> Result<(), Box<dyn Error>> {
+        let option = option.into();
+        Self::new(config).publish_configs_cas(nacos, content, types, cas_md5, option).await
+    }
+
+    /// 经过已加入的过滤器比较并交换地发布当前配置 , [cas_md5] 为服务端保存内容的 md5 ;
+    /// 配置不存在时的行为与冲突的判断见 [upload_configs_cas]
+    /// publish current config through the added filters with compare-and-swap ,
+    /// [cas_md5] is the md5 of the content saved in server .
+    /// see [upload_configs_cas] for the behavior on an absent config and how a conflict is detected .
+    pub async fn publish_configs_cas(&self, nacos: &NacosConfig, content: &str, types: Option<String>,
+                                     cas_md5: &str, option: impl Into<Option<PostConfigsOption>>)
+                                     -> Result<(), Box<dyn Error>> {
+        let option = option.into();
+        let mut map = self.publish_map(content, types)?;
+        map.insert("casMd5".to_string(), cas_md5.to_string());
+        let resp = util::query_resp(nacos, HttpRequest::post(&nacos.addr(POST_CONFIGS)), map, &option).await?;
+        let result = resp.text();
+        if result.eq("true") { return Ok(()); }
+        // 服务端对 cas 失败的响应因版本而异 , 以当前 md5 判断是否冲突
+        let current = match self.fetch_raw(nacos).await {
+            Ok((current, _)) => format!("{:x}", md5::compute(current.content)),
+            Err(_) => return Err(Box::new(NacosError::throw(&result))),
+        };
+        if current.ne(cas_md5) {
+            let reason = format!("expect md5 {} but found {}", cas_md5, current);
+            return Err(Box::new(NacosError::new(NacosErrorKind::Conflict, &reason)));
+        }
+        Err(Box::new(NacosError::throw(&result)))
+    }
+
+    /// 读取-修改-写入当前配置 : 以当前内容 ( 不存在时为空 ) 调用 [func] 并比较并交换地发布其结果 ,
+    /// 冲突时重新读取并重试 最多 [max_retries] 次 , 返回最终写入的内容 ;
+    /// 配置不存在时并发的修改都会成功 , 见 [upload_configs_cas]
+    /// read-modify-write current config : call [func] with the current content ( empty if absent ) and publish
+    /// the result with compare-and-swap , re-read and retry at most [max_retries] times on conflict ,
+    /// return the content written . concurrent modifications of an absent config all succeed ,
+    /// see [upload_configs_cas] .
+    /// ```rust,no_run
+    /// use nacos_api::{NacosConfigApi, NacosConfig, DeployConfig};
+    ///
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let nacos = NacosConfig::new("http", "192.168.0.132", 8848);
+    /// let api = NacosConfigApi::new(DeployConfig::new("feature.properties", "DEFAULT_GROUP", None));
+    /// api.modify_configs(&nacos, Some("properties".to_string()), 3, |current| {
+    ///     format!("{}\nnew.flag=true", current)
+    /// }).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn modify_configs<F>(&self, nacos: &NacosConfig, types: Option<String>, max_retries: usize, mut func: F)
+                                   -> Result<String, Box<dyn Error>>
+        where F: FnMut(&str) -> String
+    {
+        let mut retries = 0;
+        loop {
+            let (current, cas_md5) = match self.get_configs_content(nacos).await {
+                Ok(current) => (current.content, current.md5),
+                Err(e) => match e.downcast_ref::<NacosError>() {
+                    Some(ne) if ne.kind() == NacosErrorKind::NotFound => (String::new(), format!("{:x}", md5::compute(""))),
+                    _ => return Err(e),
+                }
+            };
+            let content = func(&current);
+            match self.publish_configs_cas(nacos, &content, types.clone(), &cas_md5, None).await {
+                Ok(_) => return Ok(content),
+                Err(e) => match e.downcast_ref::<NacosError>() {
+                    Some(ne) if ne.kind() == NacosErrorKind::Conflict && retries < max_retries => retries += 1,
+                    _ => return Err(e),
+                }
+            }
+        }
     }
 
     pub async fn delete_configs(nacos: &NacosConfig, config: DeployConfig)
                                 -> Result<(), Box<dyn Error>> {
         let map = config.init_map();
-        let resp = util::query(&map, |c| c.delete(nacos.addr(DELETE_CONFIGS))).await?;
-        util::resp_assert(resp, "true").await
+        let resp = util::query(nacos, HttpRequest::delete(&nacos.addr(DELETE_CONFIGS)), &map).await?;
+        util::resp_assert(resp, "true")
+    }
+
+    /// 灰度发布配置 , 仅 [beta_ips] 中的客户端能获取到该内容 ; 与 [upload_configs_with] 相同 , 拒绝 `cipher-` 前缀的 dataId
+    /// publish the config in beta , only the clients in [beta_ips] can get the content .
+    /// the same as [upload_configs_with] , the dataId prefixed by `cipher-` is refused .
+    /// ```rust,no_run
+    /// use nacos_api::{NacosConfigApi, NacosConfig, DeployConfig};
+    ///
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let nacos = NacosConfig::new("http", "192.168.0.132", 8848);
+    /// let config = DeployConfig::new("orders.yaml", "DEFAULT_GROUP", None);
+    /// let beta_ips = vec!["10.0.0.1".to_string(), "10.0.0.2".to_string()];
+    /// NacosConfigApi::upload_configs_beta(&nacos, config.clone(), "max_items: 20", None, &beta_ips, None).await?;
+    /// // promote it after verified
+    /// NacosConfigApi::upload_configs(&nacos, config.clone(), "max_items: 20", None).await?;
+    /// NacosConfigApi::delete_configs_beta(&nacos, config).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn upload_configs_beta(nacos: &NacosConfig, config: DeployConfig, content: &str, types: Option<String>,
+                                     beta_ips: &[String], option: impl Into<Option<PostConfigsOption>>)
+                                     -> Result<(), Box<dyn Error>> {
+        Self::new(config).publish_configs_beta(nacos, content, types, beta_ips, option).await
+    }
+
+    /// 经过已加入的过滤器灰度发布当前配置 , [beta_ips] 为空时返回错误
+    /// publish current config in beta through the added filters , an empty [beta_ips] is refused .
+    pub async fn publish_configs_beta(&self, nacos: &NacosConfig, content: &str, types: Option<String>,
+                                      beta_ips: &[String], option: impl Into<Option<PostConfigsOption>>)
+                                      -> Result<(), Box<dyn Error>> {
+        let option = option.into();
+        let ips: Vec<&str> = beta_ips.iter().map(|ip| ip.trim()).filter(|ip| !ip.is_empty()).collect();
+        // 没有灰度 ip 的请求会被 nacos 当作正式发布
+        if ips.is_empty() {
+            return Err(Box::new(NacosError::new(NacosErrorKind::Validate, "beta ips must not be empty")));
+        }
+        let map = self.publish_map(content, types)?;
+        let request = HttpRequest::post(&nacos.addr(POST_CONFIGS)).header(BETA_IPS_HEADER, &ips.join(","));
+        let resp = util::query_resp(nacos, request, map, &option).await?;
+        util::resp_assert(resp, "true")
+    }
+
+    /// 查询当前配置的灰度发布 , 没有灰度时返回 None
+    /// get the beta of current config , None if there is no beta .
+    pub async fn get_configs_beta(&self, nacos: &NacosConfig)
+                                  -> Result<Option<BetaConfigInfo>, Box<dyn Error>> {
+        let mut map = self.deploy_config.init_map();
+        map.insert("beta".to_string(), true.to_string());
+        let resp = util::query(nacos, HttpRequest::get(&nacos.addr(GET_CONFIGS)), &map).await?;
+        let result = resp.json::<NacosResult<BetaConfigInfo>>()?;
+        if result.code != 200 {
+            return Err(Box::new(NacosError::throw(&result.message.unwrap_or_default())));
+        }
+        Ok(result.data)
+    }
+
+    /// 停止配置的灰度发布
+    /// stop the beta of the config .
+    pub async fn delete_configs_beta(nacos: &NacosConfig, config: DeployConfig)
+                                     -> Result<(), Box<dyn Error>> {
+        let mut map = config.init_map();
+        map.insert("beta".to_string(), true.to_string());
+        let resp = util::query(nacos, HttpRequest::delete(&nacos.addr(DELETE_CONFIGS)), &map).await?;
+        let result = resp.json::<NacosResult<bool>>()?;
+        if result.code != 200 || result.data != Some(true) {
+            return Err(Box::new(NacosError::throw(&result.message.unwrap_or_default())));
+        }
+        Ok(())
+    }
+
+    /// 分页查询当前配置的历史修改 , 列表中不包含配置内容
+    /// get the history of current config by page , the content is not included .
+    pub async fn get_history(&self, nacos: &NacosConfig, page_no: i32, page_size: i32)
+                             -> Result<NacosPage<ConfigHistory>, Box<dyn Error>> {
+        let mut map = self.deploy_config.init_map();
+        map.insert("search".to_string(), "accurate".to_string());
+        map.insert("pageNo".to_string(), page_no.to_string());
+        map.insert("pageSize".to_string(), page_size.to_string());
+        let resp = util::query(nacos, HttpRequest::get(&nacos.addr(GET_HISTORY)), &map).await?;
+        if !resp.is_success() { return Err(Box::new(NacosError::throw(&resp.text()))); }
+        let result = resp.json::<NacosPage<ConfigHistory>>()?;
+        Ok(result)
+    }
+
+    /// 获取指定 nid 的历史修改 , 包含当时的配置内容
+    /// get the history of the specified nid , with the content at that time .
+    pub async fn get_history_detail(&self, nacos: &NacosConfig, nid: i64)
+                                    -> Result<ConfigHistory, Box<dyn Error>> {
+        let mut map = self.deploy_config.init_map();
+        map.insert("nid".to_string(), nid.to_string());
+        let resp = util::query(nacos, HttpRequest::get(&nacos.addr(GET_HISTORY)), &map).await?;
+        history_assert(&resp)?;
+        let result = resp.json::<ConfigHistory>()?;
+        Ok(result)
+    }
+
+    /// 获取配置变更记录 [id] 的上一次修改
+    /// get the previous history of the config change [id] .
+    pub async fn get_previous_history(&self, nacos: &NacosConfig, id: i64)
+                                      -> Result<ConfigHistory, Box<dyn Error>> {
+        let mut map = self.deploy_config.init_map();
+        map.insert("id".to_string(), id.to_string());
+        let resp = util::query(nacos, HttpRequest::get(&nacos.addr(GET_HISTORY_PREVIOUS)), &map).await?;
+        history_assert(&resp)?;
+        let result = resp.json::<ConfigHistory>()?;
+        Ok(result)
+    }
+
+    /// 将当前配置回滚到历史记录 [nid] 保存的内容及 type ( 与控制台的回滚一致 重新发布该内容 ) ,
+    /// 加密配置连同其数据密钥原样发布 , 不经过过滤器 ; 历史记录没有内容时返回 NotFound 错误
+    /// rollback current config to the content and type saved in the history [nid] by republishing it ,
+    /// the same as the rollback of nacos console . encrypted configs are republished as is with their data keys ,
+    /// without the filters . return a NotFound err if the history has no content .
+    /// ```rust,no_run
+    /// use nacos_api::{NacosConfigApi, NacosConfig, DeployConfig};
+    ///
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let nacos = NacosConfig::new("http", "192.168.0.132", 8848);
+    /// let api = NacosConfigApi::new(DeployConfig::new("test_data", "test_grep", None));
+    /// let history = api.get_history(&nacos, 1, 10).await?;
+    /// if let Some(last) = history.page_items.get(1) {
+    ///     api.rollback_to(&nacos, last.id).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn rollback_to(&self, nacos: &NacosConfig, nid: i64)
+                             -> Result<(), Box<dyn Error>> {
+        let history = self.get_history_detail(nacos, nid).await?;
+        let content = match history.content {
+            Some(content) if !content.is_empty() => content,
+            _ => return Err(Box::new(NacosError::new(NacosErrorKind::NotFound,
+                                                     &format!("config history {} has no content", nid)))),
+        };
+        let mut map = self.deploy_config.init_map();
+        map.insert("content".to_string(), content);
+        if let Some(t) = history.config_type { map.insert("type".to_string(), t); }
+        if let Some(key) = history.encrypted_data_key { map.insert("encryptedDataKey".to_string(), key); }
+        let resp = util::query(nacos, HttpRequest::post(&nacos.addr(POST_CONFIGS)), &map).await?;
+        util::resp_assert(resp, "true")
+    }
+
+    /// 分页搜索配置 , 精确搜索时 dataId 与 group 为空表示不限 , 模糊搜索时支持 `*` 通配
+    /// search configs by page , empty dataId and group mean unlimited when accurate search ,
+    /// and `*` wildcard is supported when blur search .
+    pub async fn search(nacos: &NacosConfig, mode: SearchMode, page_no: i32, page_size: i32, option: impl Into<Option<SearchConfigsOption>>)
+                        -> Result<NacosPage<ConfigInfo>, Box<dyn Error>> {
+        let option = option.into();
+        let mut map = HashMap::<String, String>::new();
+        map.insert("search".to_string(), mode.as_str().to_string());
+        map.insert("pageNo".to_string(), page_no.to_string());
+        map.insert("pageSize".to_string(), page_size.to_string());
+        map.insert("dataId".to_string(), String::new());
+        map.insert("group".to_string(), String::new());
+        let resp = util::query_resp(nacos, HttpRequest::get(&nacos.addr(GET_CONFIGS_SEARCH)), map, &option).await?;
+        if !resp.is_success() { return Err(Box::new(NacosError::throw(&resp.text()))); }
+        let result = resp.json::<NacosPage<ConfigInfo>>()?;
+        Ok(result)
+    }
+
+    /// 逐页遍历所有搜索结果
+    /// walk through all pages of the search result .
+    /// ```rust,no_run
+    /// use nacos_api::{NacosConfigApi, NacosConfig};
+    /// use nacos_api::model::service_dto::{SearchConfigsOption, SearchMode};
+    ///
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let nacos = NacosConfig::new("http", "192.168.0.132", 8848);
+    /// let option = SearchConfigsOption::builder().data_id("orders*");
+    /// let mut configs = NacosConfigApi::search_all(&nacos, SearchMode::Blur, 100, option);
+    /// while let Some(config) = configs.next().await {
+    ///     println!("{}", config?.data_id);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn search_all(nacos: &NacosConfig, mode: SearchMode, page_size: i32,
+                      option: impl Into<Option<SearchConfigsOption>>) -> ConfigSearch {
+        ConfigSearch {
+            nacos: nacos.clone(),
+            mode,
+            page_size,
+            option: option.into(),
+            next_page: 1,
+            pages_available: None,
+            buffer: VecDeque::new(),
+        }
+    }
+
+    /// 以 nacos 的 zip 格式导出配置 , 可用于 [import_configs] 导入
+    /// export configs in the zip format of nacos , which can be imported by [import_configs] .
+    pub async fn export_configs(nacos: &NacosConfig, option: impl Into<Option<ExportConfigsOption>>)
+                                -> Result<Vec<u8>, Box<dyn Error>> {
+        let option = option.into();
+        let mut map = HashMap::<String, String>::new();
+        map.insert("export".to_string(), true.to_string());
+        let resp = util::query_resp(nacos, HttpRequest::get(&nacos.addr(GET_CONFIGS_EXPORT)), map, &option).await?;
+        if !resp.is_success() { return Err(Box::new(NacosError::throw(&resp.text()))); }
+        Ok(resp.body)
+    }
+
+    /// 将 nacos zip 格式的配置导入到命名空间 [namespace] ( None 为 public ) , 同名配置按 [policy] 处理
+    /// import the configs in nacos zip format into [namespace] ( None for public ) ,
+    /// the existing configs are handled by [policy] .
+    /// ```rust,no_run
+    /// use nacos_api::{NacosConfigApi, NacosConfig};
+    /// use nacos_api::model::service_dto::{ExportConfigsOption, SameConfigPolicy};
+    ///
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let nacos = NacosConfig::new("http", "192.168.0.132", 8848);
+    /// let option = ExportConfigsOption::builder().tenant("dev");
+    /// let zip = NacosConfigApi::export_configs(&nacos, option).await?;
+    /// let summary = NacosConfigApi::import_configs(&nacos, Some("test"), SameConfigPolicy::Skip, zip).await?;
+    /// println!("succeeded {} skipped {} failed {:?}", summary.succeeded, summary.skipped, summary.failed_items);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn import_configs(nacos: &NacosConfig, namespace: Option<&str>, policy: SameConfigPolicy, zip: Vec<u8>)
+                                -> Result<ConfigImportSummary, Box<dyn Error>> {
+        let mut map = HashMap::<String, String>::new();
+        map.insert("import".to_string(), true.to_string());
+        map.insert("policy".to_string(), policy.as_str().to_string());
+        if let Some(ns) = namespace { map.insert("namespace".to_string(), ns.to_string()); }
+        let request = HttpRequest::post(&nacos.addr(POST_CONFIGS_IMPORT)).file("file", "nacos_config.zip", zip);
+        let resp = util::query(nacos, request, &map).await?;
+        Self::import_summary(resp)
     }
-}
\ No newline at end of file
+
+    /// 将配置克隆到命名空间 [target_namespace] ( None 为 public ) , 同名配置按 [policy] 处理
+    /// clone the configs into [target_namespace] ( None for public ) , the existing configs are handled by [policy] .
+    pub async fn clone_configs(nacos: &NacosConfig, target_namespace: Option<&str>, policy: SameConfigPolicy, configs: &[ConfigInfo])
+                               -> Result<ConfigImportSummary, Box<dyn Error>> {
+        let mut map = HashMap::<String, String>::new();
+        map.insert("clone".to_string(), true.to_string());
+        map.insert("policy".to_string(), policy.as_str().to_string());
+        map.insert("tenant".to_string(), target_namespace.unwrap_or_default().to_string());
+        let body: Vec<_> = configs.iter()
+            .map(|c| json!({ "cfgId": c.id, "dataId": c.data_id, "group": c.group }))
+            .collect();
+        let request = HttpRequest::post(&nacos.addr(POST_CONFIGS_CLONE)).json(json!(body));
+        let resp = util::query(nacos, request, &map).await?;
+        Self::import_summary(resp)
+    }
+
+    /// 将命名空间 [source_namespace] 的所有配置克隆到 [target_namespace] ( None 为 public )
+    /// clone all configs of [source_namespace] into [target_namespace] ( None for public ) .
+    pub async fn clone_namespace(nacos: &NacosConfig, source_namespace: Option<&str>, target_namespace: Option<&str>,
+                                 policy: SameConfigPolicy)
+                                 -> Result<ConfigImportSummary, Box<dyn Error>> {
+        let mut option = SearchConfigsOption::default();
+        option.set_tenant(source_namespace.map(|s| s.to_string()));
+        let mut search = Self::search_all(nacos, SearchMode::Blur, 100, option);
+        let mut configs = Vec::new();
+        while let Some(page) = search.next_page().await {
+            configs.extend(page?);
+        }
+        if configs.is_empty() { return Ok(ConfigImportSummary::default()); }
+        Self::clone_configs(nacos, target_namespace, policy, &configs).await
+    }
+
+    fn import_summary(resp: HttpResponse) -> Result<ConfigImportSummary, Box<dyn Error>> {
+        let result = resp.json::<NacosResult<ConfigImportSummary>>()?;
+        if result.code != 200 {
+            return Err(Box::new(NacosError::throw(&result.message.unwrap_or_default())));
+        }
+        Ok(result.data.unwrap_or_default())
+    }
+}
+
+/// 历史记录不存在时 nacos 返回 404 , 其他错误原样返回
+fn history_assert(resp: &HttpResponse) -> Result<(), Box<dyn Error>> {
+    if resp.status == 404 {
+        return Err(Box::new(NacosError::new(NacosErrorKind::NotFound, "config history not exist")));
+    }
+    if !resp.is_success() { return Err(Box::new(NacosError::throw(&resp.text()))); }
+    Ok(())
+}
+
+/// 逐页拉取的配置搜索结果 , 需要时才请求下一页
+/// the config search result fetched page by page , the next page is requested only when needed .
+pub struct ConfigSearch {
+    nacos: NacosConfig,
+    mode: SearchMode,
+    page_size: i32,
+    option: Option<SearchConfigsOption>,
+    next_page: i32,
+    pages_available: Option<i64>,
+    buffer: VecDeque<ConfigInfo>,
+}
+
+impl ConfigSearch {
+    /// 下一个配置 , 所有页遍历完后返回 None
+    pub async fn next(&mut self) -> Option<Result<ConfigInfo, NacosError>> {
+        while self.buffer.is_empty() {
+            match self.next_page().await? {
+                Ok(page) => self.buffer.extend(page),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        self.buffer.pop_front().map(Ok)
+    }
+
+    /// 下一页配置 , 所有页遍历完后返回 None
+    pub async fn next_page(&mut self) -> Option<Result<Vec<ConfigInfo>, NacosError>> {
+        if let Some(pages) = self.pages_available {
+            if self.next_page as i64 > pages { return None; }
+        }
+        let page = NacosConfigApi::search(&self.nacos, self.mode, self.next_page, self.page_size, self.option.clone())
+            .await
+            .map_err(NacosError::from_boxed);
+        match page {
+            Ok(page) => {
+                self.next_page += 1;
+                // 空页之后不再请求
+                self.pages_available = Some(if page.page_items.is_empty() { 0 } else { page.pages_available });
+                Some(Ok(page.page_items))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::model::err::{NacosError, NacosErrorKind};
+use aes::cipher::{BlockDecryptMut, BlockEncryptMut, KeyInit};
+use aes::cipher::block_padding::Pkcs7;
+use aes::{Aes128, Aes192, Aes256};
+use rand::Rng;
+use rand::distributions::Alphanumeric;
+
+/// 经过过滤器的配置 , 发布前为明文 , 获取后为服务端保存的内容
+/// the config passed through filters , plaintext before publish and the content saved in server after fetch .
+#[derive(Debug, Clone)]
+pub struct FilteredConfig {
+    pub data_id: String,
+    pub group: String,
+    pub tenant: Option<String>,
+    pub content: String,
+    /// 加密后的数据密钥 , 随配置保存在服务端
+    pub encrypted_data_key: Option<String>,
+}
+
+/// 配置过滤器 , 在发布前和获取后处理配置内容 ,
+/// the config filter , handling the config content before publish and after fetch .
+pub trait ConfigFilter: Send + Sync {
+    /// 发布前调用 , 按过滤器加入的顺序执行
+    fn before_publish(&self, config: &mut FilteredConfig) -> Result<(), NacosError>;
+    /// 获取后调用 , 按过滤器加入的逆序执行
+    fn after_fetch(&self, config: &mut FilteredConfig) -> Result<(), NacosError>;
+}
+
+/// nacos 配置加密插件的 AES 实现 , 只处理 `cipher-aes-` 前缀的 dataId ;
+/// 每次发布生成随机数据密钥加密内容 , 数据密钥由主密钥加密后随配置保存
+/// the AES implementation of the nacos config encryption plugin , only handles the dataId prefixed by `cipher-aes-` .
+/// every publish encrypts the content with a random data key , which is saved with the config after encrypted
+/// by the master key .
+pub struct AesConfigFilter {
+    secret_key: Vec<u8>,
+}
+
+/// 加密配置的 dataId 前缀 , 这类配置只能经过加密过滤器发布
+pub const CIPHER_PREFIX: &str = "cipher-";
+/// AES 加密的 dataId 前缀
+pub const AES_CIPHER_PREFIX: &str = "cipher-aes-";
+
+impl AesConfigFilter {
+    /// [secret_key] 为主密钥 , 长度必须为 16 24 或 32 字节
+    pub fn new(secret_key: &str) -> Result<Self, NacosError> {
+        match secret_key.len() {
+            16 | 24 | 32 => Ok(Self { secret_key: secret_key.as_bytes().to_vec() }),
+            n => Err(NacosError::new(NacosErrorKind::Validate,
+                                     &format!("aes secret key must be 16, 24 or 32 bytes but got {}", n))),
+        }
+    }
+
+    fn handles(config: &FilteredConfig) -> bool {
+        config.data_id.starts_with(AES_CIPHER_PREFIX)
+    }
+}
+
+impl ConfigFilter for AesConfigFilter {
+    fn before_publish(&self, config: &mut FilteredConfig) -> Result<(), NacosError> {
+        if !Self::handles(config) { return Ok(()); }
+        let data_key: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(16)
+            .map(char::from)
+            .collect();
+        config.content = base64::encode(encrypt(data_key.as_bytes(), config.content.as_bytes())?);
+        config.encrypted_data_key = Some(base64::encode(encrypt(&self.secret_key, data_key.as_bytes())?));
+        Ok(())
+    }
+
+    fn after_fetch(&self, config: &mut FilteredConfig) -> Result<(), NacosError> {
+        if !Self::handles(config) { return Ok(()); }
+        let encrypted_key = match config.encrypted_data_key.as_deref() {
+            Some(key) if !key.is_empty() => key,
+            _ => return Err(cipher_error("missing encrypted data key")),
+        };
+        let data_key = decrypt(&self.secret_key, &decode(encrypted_key)?)?;
+        let content = decrypt(&data_key, &decode(&config.content)?)?;
+        config.content = String::from_utf8(content).map_err(|e| cipher_error(&e.to_string()))?;
+        Ok(())
+    }
+}
+
+/// AES/ECB/PKCS5Padding , 与 nacos 插件一致
+fn encrypt(key: &[u8], data: &[u8]) -> Result<Vec<u8>, NacosError> {
+    let invalid = |_| cipher_error("invalid aes key length");
+    Ok(match key.len() {
+        16 => ecb::Encryptor::<Aes128>::new_from_slice(key).map_err(invalid)?.encrypt_padded_vec_mut::<Pkcs7>(data),
+        24 => ecb::Encryptor::<Aes192>::new_from_slice(key).map_err(invalid)?.encrypt_padded_vec_mut::<Pkcs7>(data),
+        _ => ecb::Encryptor::<Aes256>::new_from_slice(key).map_err(invalid)?.encrypt_padded_vec_mut::<Pkcs7>(data),
+    })
+}
+
+fn decrypt(key: &[u8], data: &[u8]) -> Result<Vec<u8>, NacosError> {
+    let invalid = |_| cipher_error("invalid aes key length");
+    let result = match key.len() {
+        16 => ecb::Decryptor::<Aes128>::new_from_slice(key).map_err(invalid)?.decrypt_padded_vec_mut::<Pkcs7>(data),
+        24 => ecb::Decryptor::<Aes192>::new_from_slice(key).map_err(invalid)?.decrypt_padded_vec_mut::<Pkcs7>(data),
+        _ => ecb::Decryptor::<Aes256>::new_from_slice(key).map_err(invalid)?.decrypt_padded_vec_mut::<Pkcs7>(data),
+    };
+    result.map_err(|_| cipher_error("bad padding , wrong secret key ?"))
+}
+
+fn decode(s: &str) -> Result<Vec<u8>, NacosError> {
+    base64::decode(s.trim()).map_err(|e| cipher_error(&e.to_string()))
+}
+
+fn cipher_error(reason: &str) -> NacosError {
+    NacosError::new(NacosErrorKind::Parse, &format!("config decrypt/encrypt err : {}", reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(data_id: &str, content: &str) -> FilteredConfig {
+        FilteredConfig {
+            data_id: data_id.to_string(),
+            group: "DEFAULT_GROUP".to_string(),
+            tenant: None,
+            content: content.to_string(),
+            encrypted_data_key: None,
+        }
+    }
+
+    #[test]
+    fn aes_round_trip() {
+        for secret_key in ["0123456789abcdef", "0123456789abcdef01234567", "0123456789abcdef0123456789abcdef"] {
+            let filter = AesConfigFilter::new(secret_key).unwrap();
+            let mut published = config("cipher-aes-db.yaml", "password: 密码");
+            filter.before_publish(&mut published).unwrap();
+            assert_ne!("password: 密码", published.content);
+            assert!(published.encrypted_data_key.is_some());
+
+            let mut fetched = published.clone();
+            filter.after_fetch(&mut fetched).unwrap();
+            assert_eq!("password: 密码", fetched.content);
+
+            // 每次发布使用新的数据密钥
+            let mut again = config("cipher-aes-db.yaml", "password: 密码");
+            filter.before_publish(&mut again).unwrap();
+            assert_ne!(published.encrypted_data_key, again.encrypted_data_key);
+        }
+    }
+
+    #[test]
+    fn aes_wrong_key_and_plain_data_id() {
+        let mut published = config("cipher-aes-db.yaml", "password: secret");
+        AesConfigFilter::new("0123456789abcdef").unwrap().before_publish(&mut published).unwrap();
+        let err = AesConfigFilter::new("fedcba9876543210").unwrap().after_fetch(&mut published.clone());
+        assert_eq!(NacosErrorKind::Parse, err.err().unwrap().kind());
+
+        let filter = AesConfigFilter::new("0123456789abcdef").unwrap();
+        let mut plain = config("db.yaml", "password: secret");
+        filter.before_publish(&mut plain).unwrap();
+        assert_eq!("password: secret", plain.content);
+        assert!(plain.encrypted_data_key.is_none());
+        assert!(AesConfigFilter::new("short").is_err());
+    }
+}
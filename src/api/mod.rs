@@ -1,3 +1,4 @@
 pub mod config;
+pub mod filter;
 pub mod namespace;
 pub mod service;
\ No newline at end of file
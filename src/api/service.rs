@@ -1,9 +1,10 @@
 use crate::model::{NacosConfig, ServerConfig};
 use std::error::Error;
-use crate::model::service_dto::{RegisterInstanceOption, RemoveInstanceOption, UpdateInstanceOption, GetInstanceOption, PostServiceOption, DeleteServiceOption, PutServiceOption, GetServiceOption};
+use crate::model::service_dto::{RegisterInstanceOption, RemoveInstanceOption, UpdateInstanceOption, UpdateHealthOption, GetInstanceOption, PostServiceOption, DeleteServiceOption, PutServiceOption, GetServiceOption};
 use std::collections::HashMap;
 use crate::model::service_vo::{NacosServerView, NacosHost, NacosBeat, NacosServiceInfo, NacosServiceList, NacosMetrics, NacosServerSimpleView};
-use crate::util::{self, CLIENT};
+use crate::util;
+use crate::util::transport::HttpRequest;
 
 const POST_INSTANCE: &str = "/v1/ns/instance";
 const DELETE_INSTANCE: &str = "/v1/ns/instance";
@@ -11,6 +12,7 @@ const PUT_INSTANCE: &str = "/v1/ns/instance";
 const GET_INSTANCE_LIST: &str = "/v1/ns/instance/list";
 const GET_INSTANCE: &str = "/v1/ns/instance";
 const PUT_INSTANCE_BEAT: &str = "/v1/ns/instance/beat";
+const PUT_HEALTH_INSTANCE: &str = "/v1/ns/health/instance";
 
 const POST_SERVICE: &str = "/v1/ns/service";
 const DELETE_SERVICE: &str = "/v1/ns/service";
@@ -36,34 +38,47 @@ impl NacosServiceApi {
 
 impl NacosServiceApi {
     /// register a new instance .
-    pub async fn register_instance(&self, nacos_config: &NacosConfig, option: &Option<RegisterInstanceOption>)
+    pub async fn register_instance(&self, nacos_config: &NacosConfig, option: impl Into<Option<RegisterInstanceOption>>)
                                    -> Result<(), Box<dyn Error>> {
+        let option = option.into();
         let map = self.server_config.init_map();
-        util::query_and_ok(map, option, |c| c.post(nacos_config.addr(POST_INSTANCE))).await
+        util::query_and_ok(nacos_config, HttpRequest::post(&nacos_config.addr(POST_INSTANCE)), map, &option).await
     }
 
     /// remove current instance .
-    pub async fn remove_instance(&self, nacos_config: &NacosConfig, option: &Option<RemoveInstanceOption>)
+    pub async fn remove_instance(&self, nacos_config: &NacosConfig, option: impl Into<Option<RemoveInstanceOption>>)
                                  -> Result<(), Box<dyn Error>> {
+        let option = option.into();
         let map = self.server_config.init_map();
-        util::query_and_ok(map, option, |c| c.delete(nacos_config.addr(DELETE_INSTANCE))).await
+        util::query_and_ok(nacos_config, HttpRequest::delete(&nacos_config.addr(DELETE_INSTANCE)), map, &option).await
     }
 
     /// update current instance .
-    pub async fn update_instance(&self, nacos_config: &NacosConfig, option: &Option<UpdateInstanceOption>)
+    pub async fn update_instance(&self, nacos_config: &NacosConfig, option: impl Into<Option<UpdateInstanceOption>>)
                                  -> Result<(), Box<dyn Error>> {
+        let option = option.into();
         let map = self.server_config.init_map();
-        util::query_and_ok(map, option, |c| c.put(nacos_config.addr(PUT_INSTANCE))).await
+        util::query_and_ok(nacos_config, HttpRequest::put(&nacos_config.addr(PUT_INSTANCE)), map, &option).await
+    }
+
+    /// 更新当前实例的健康状态 , 仅对持久化实例有效
+    /// update the health state of current instance , only works for persistent instance .
+    pub async fn update_instance_health(&self, nacos_config: &NacosConfig, healthy: bool, option: impl Into<Option<UpdateHealthOption>>)
+                                        -> Result<(), Box<dyn Error>> {
+        let option = option.into();
+        let mut map = self.server_config.init_map();
+        map.insert("healthy".to_string(), healthy.to_string());
+        util::query_and_ok(nacos_config, HttpRequest::put(&nacos_config.addr(PUT_HEALTH_INSTANCE)), map, &option).await
     }
 
     /// get instance list .
-    pub async fn get_instance_list(nacos_config: &NacosConfig, service_name: &str, option: &Option<GetInstanceOption>)
+    pub async fn get_instance_list(nacos_config: &NacosConfig, service_name: &str, option: impl Into<Option<GetInstanceOption>>)
                                    -> Result<NacosServerView, Box<dyn Error>> {
+        let option = option.into();
         let mut map = HashMap::<String, String>::new();
         map.insert("serviceName".to_string(), service_name.to_string());
-        let resp = util::query_resp(map, option, |c|
-            c.get(nacos_config.addr(GET_INSTANCE_LIST))).await?;
-        let result = resp.json::<NacosServerView>().await?;
+        let resp = util::query_resp(nacos_config, HttpRequest::get(&nacos_config.addr(GET_INSTANCE_LIST)), map, &option).await?;
+        let result = resp.json::<NacosServerView>()?;
         Ok(result)
     }
 
@@ -72,8 +87,9 @@ impl NacosServiceApi {
                               service_name: &str,
                               service_ip: &str,
                               service_port: u16,
-                              option: &Option<GetInstanceOption>,
+                              option: impl Into<Option<GetInstanceOption>>,
     ) -> Result<NacosHost, Box<dyn Error>> {
+        let option = option.into();
         let s = Self::get_instance_str(
             nacos_config,
             service_name,
@@ -89,25 +105,24 @@ impl NacosServiceApi {
                                   service_name: &str,
                                   service_ip: &str,
                                   service_port: u16,
-                                  option: &Option<GetInstanceOption>, )
+                                  option: impl Into<Option<GetInstanceOption>>, )
                                   -> Result<String, Box<dyn Error>> {
+        let option = option.into();
         let mut map = HashMap::<String, String>::new();
         map.insert("serviceName".to_string(), service_name.to_string());
         map.insert("ip".to_string(), service_ip.to_string());
         map.insert("port".to_string(), service_port.to_string());
-        let resp = util::query_resp(map, option, |c|
-            c.get(nacos_config.addr(GET_INSTANCE))).await?;
-        let result = resp.text().await?;
+        let resp = util::query_resp(nacos_config, HttpRequest::get(&nacos_config.addr(GET_INSTANCE)), map, &option).await?;
+        let result = resp.text();
         Ok(result)
     }
 
     /// send hart beat
     pub async fn hart_beat(&self, nacos_config: &NacosConfig)
                            -> Result<NacosBeat, Box<dyn Error + Send + Sync>> {
-        let resp = CLIENT
-            .put(nacos_config.addr(PUT_INSTANCE_BEAT))
-            .query(&self.server_config.init_map()).send().await?;
-        let result = resp.json::<NacosBeat>().await?;
+        let request = HttpRequest::put(&nacos_config.addr(PUT_INSTANCE_BEAT)).query(&self.server_config.init_map());
+        let resp = nacos_config.transport().send(request).await?;
+        let result = resp.json::<NacosBeat>()?;
         Ok(result)
     }
 
@@ -116,83 +131,89 @@ impl NacosServiceApi {
                                   -> Result<NacosBeat, Box<dyn Error + Send + Sync>> {
         let mut map = self.server_config.init_map();
         map.insert("beat".to_string(), beat.to_string());
-        let resp = CLIENT
-            .put(nacos_config.addr(PUT_INSTANCE_BEAT))
-            .query(&map).send().await?;
-        let result = resp.json::<NacosBeat>().await?;
+        let request = HttpRequest::put(&nacos_config.addr(PUT_INSTANCE_BEAT)).query(&map);
+        let resp = nacos_config.transport().send(request).await?;
+        let result = resp.json::<NacosBeat>()?;
         Ok(result)
     }
 
     /// create a new server .
-    pub async fn create_server(nacos_config: &NacosConfig, service_name: &str, option: &Option<PostServiceOption>)
+    pub async fn create_server(nacos_config: &NacosConfig, service_name: &str, option: impl Into<Option<PostServiceOption>>)
                                -> Result<(), Box<dyn Error>> {
+        let option = option.into();
         let mut map = HashMap::<String, String>::new();
         map.insert("serviceName".to_string(), service_name.to_string());
-        util::query_and_ok(map, option, |c| c.post(nacos_config.addr(POST_SERVICE))).await
+        util::query_and_ok(nacos_config, HttpRequest::post(&nacos_config.addr(POST_SERVICE)), map, &option).await
     }
 
     /// delete a server by server name and option .
-    pub async fn delete_server(nacos_config: &NacosConfig, service_name: &str, option: &Option<DeleteServiceOption>)
+    pub async fn delete_server(nacos_config: &NacosConfig, service_name: &str, option: impl Into<Option<DeleteServiceOption>>)
                                -> Result<(), Box<dyn Error>> {
+        let option = option.into();
         let mut map = HashMap::<String, String>::new();
         map.insert("serviceName".to_string(), service_name.to_string());
-        util::query_and_ok(map, option, |c| c.delete(nacos_config.addr(DELETE_SERVICE))).await
+        util::query_and_ok(nacos_config, HttpRequest::delete(&nacos_config.addr(DELETE_SERVICE)), map, &option).await
     }
 
     /// update a server by server name and option .
-    pub async fn update_server(nacos_config: &NacosConfig, service_name: &str, option: &Option<PutServiceOption>)
+    pub async fn update_server(nacos_config: &NacosConfig, service_name: &str, option: impl Into<Option<PutServiceOption>>)
                                -> Result<(), Box<dyn Error>> {
+        let option = option.into();
         let mut map = HashMap::<String, String>::new();
         map.insert("serviceName".to_string(), service_name.to_string());
         map.insert("protectThreshold".to_string(), "0".to_string());
-        util::query_and_ok(map, option, |c| c.put(nacos_config.addr(PUT_SERVICE))).await
+        util::query_and_ok(nacos_config, HttpRequest::put(&nacos_config.addr(PUT_SERVICE)), map, &option).await
     }
 
     /// get a server by server name and option
-    pub async fn get_server(nacos_config: &NacosConfig, service_name: &str, option: &Option<GetServiceOption>)
+    pub async fn get_server(nacos_config: &NacosConfig, service_name: &str, option: impl Into<Option<GetServiceOption>>)
                             -> Result<NacosServiceInfo, Box<dyn Error>> {
+        let option = option.into();
         let mut map = HashMap::<String, String>::new();
         map.insert("serviceName".to_string(), service_name.to_string());
-        let resp = util::query_resp(map, option, |c| c.get(nacos_config.addr(GET_SERVICE))).await?;
-        let result = resp.json::<NacosServiceInfo>().await?;
+        let resp = util::query_resp(nacos_config, HttpRequest::get(&nacos_config.addr(GET_SERVICE)), map, &option).await?;
+        let result = resp.json::<NacosServiceInfo>()?;
         Ok(result)
     }
 
     /// get a server list by page limit
-    pub async fn get_server_list(nacos_config: &NacosConfig, page_no: i32, page_size: i32, option: &Option<GetServiceOption>)
+    pub async fn get_server_list(nacos_config: &NacosConfig, page_no: i32, page_size: i32, option: impl Into<Option<GetServiceOption>>)
                                  -> Result<NacosServiceList, Box<dyn Error>> {
+        let option = option.into();
         let mut map = HashMap::<String, String>::new();
         map.insert("pageNo".to_string(), page_no.to_string());
         map.insert("pageSize".to_string(), page_size.to_string());
-        let resp = util::query_resp(map, option, |c| c.get(nacos_config.addr(GET_SERVICE_LIST))).await?;
-        let result = resp.json::<NacosServiceList>().await?;
+        let resp = util::query_resp(nacos_config, HttpRequest::get(&nacos_config.addr(GET_SERVICE_LIST)), map, &option).await?;
+        let result = resp.json::<NacosServiceList>()?;
         Ok(result)
     }
 
     /// get current nacos cluster running state .
     pub async fn get_operator_metrics(nacos_config: &NacosConfig)
                                       -> Result<NacosMetrics, Box<dyn Error>> {
-        let resp = reqwest::get(nacos_config.addr(GET_OPERATOR_METRICS)).await?;
-        let result = resp.json::<NacosMetrics>().await?;
+        let resp = nacos_config.transport().send(HttpRequest::get(&nacos_config.addr(GET_OPERATOR_METRICS))).await?;
+        let result = resp.json::<NacosMetrics>()?;
         Ok(result)
     }
 
     /// get current nacos cluster server info .
-    /// ```rust
+    /// ```rust,no_run
     /// use nacos_api::{NacosServiceApi, NacosConfig, NacosClient, ServerConfig};
     ///
+    /// # async fn run() {
     /// let nacos_config = NacosConfig::new("http","192.168.0.132",8848);
     /// //call by nacos_config
     /// let result = NacosServiceApi::get_operator_servers(&nacos_config).await;
     ///
-    /// let client = NacosClient::new(&nacos_config,ServerConfig::new("127.0.0.1",8080,"test"));
+    /// let client = NacosClient::new(&nacos_config,ServerConfig::new("127.0.0.1",8080,"test",None,None));
     /// //call by client
     /// let result = NacosServiceApi::get_operator_servers(client.nacos_config()).await;
+    /// # }
     /// ```
     pub async fn get_operator_servers(nacos_config: &NacosConfig)
                                       -> Result<NacosServerSimpleView, Box<dyn Error>> {
-        let resp = reqwest::get(nacos_config.addr(GET_OPERATOR_SERVERS)).await?;
-        let result = resp.json::<NacosServerSimpleView>().await?;
+        let resp = nacos_config.transport().send(HttpRequest::get(&nacos_config.addr(GET_OPERATOR_SERVERS))).await?;
+        let result = resp.json::<NacosServerSimpleView>()?;
         Ok(result)
     }
 }
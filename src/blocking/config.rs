@@ -0,0 +1,341 @@
+use crate::api::config as nonblocking;
+use crate::api::filter::ConfigFilter;
+use crate::blocking::{block_on, enter, BackgroundTask};
+use crate::integration::configs as nonblocking_client;
+use crate::model::{DeployConfig, NacosConfig};
+use crate::model::config_format::ConfigFormat;
+use crate::model::config_vo::{BetaConfigInfo, ConfigChange, ConfigContent, ConfigHistory, ConfigImportSummary, ConfigInfo, NacosPage};
+use crate::model::err::NacosError;
+use crate::model::service_dto::{ExportConfigsOption, PostConfigsOption, SameConfigPolicy, SearchConfigsOption, SearchMode};
+use crate::LiveConfig;
+use serde::de::DeserializeOwned;
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time;
+
+/// [crate::NacosConfigApi] 的同步版本
+/// the synchronous version of [crate::NacosConfigApi] .
+#[derive(Clone)]
+pub struct NacosConfigApi {
+    inner: crate::NacosConfigApi,
+}
+
+impl From<crate::NacosConfigApi> for NacosConfigApi {
+    fn from(inner: crate::NacosConfigApi) -> Self { Self { inner } }
+}
+
+impl NacosConfigApi {
+    pub fn new(config: DeployConfig) -> Self {
+        Self::from(crate::NacosConfigApi::new(config))
+    }
+    pub fn deploy_config(&self) -> &DeployConfig {
+        self.inner.deploy_config()
+    }
+    pub fn with_filter(mut self, filter: Arc<dyn ConfigFilter>) -> Self {
+        self.add_filter(filter);
+        self
+    }
+    pub fn add_filter(&mut self, filter: Arc<dyn ConfigFilter>) {
+        self.inner.add_filter(filter)
+    }
+    pub fn filters(&self) -> &[Arc<dyn ConfigFilter>] {
+        self.inner.filters()
+    }
+}
+
+impl NacosConfigApi {
+    pub fn get_configs(&self, nacos: &NacosConfig) -> Result<String, Box<dyn Error>> {
+        block_on(self.inner.get_configs(nacos))
+    }
+
+    pub fn get_configs_content(&self, nacos: &NacosConfig) -> Result<ConfigContent, Box<dyn Error>> {
+        block_on(self.inner.get_configs_content(nacos))
+    }
+
+    pub fn listen_configs(&self, nacos: &NacosConfig, content_md5: &str, timeout_ms: u64)
+                          -> Result<bool, Box<dyn Error + Send + Sync>> {
+        block_on(self.inner.listen_configs(nacos, content_md5, timeout_ms))
+    }
+
+    pub fn upload_configs(nacos: &NacosConfig, config: DeployConfig, content: &str, types: Option<String>)
+                          -> Result<(), Box<dyn Error>> {
+        block_on(crate::NacosConfigApi::upload_configs(nacos, config, content, types))
+    }
+
+    pub fn upload_configs_with(nacos: &NacosConfig, config: DeployConfig, content: &str, types: Option<String>,
+                               option: impl Into<Option<PostConfigsOption>>)
+                               -> Result<(), Box<dyn Error>> {
+        block_on(crate::NacosConfigApi::upload_configs_with(nacos, config, content, types, option))
+    }
+
+    pub fn publish_configs(&self, nacos: &NacosConfig, content: &str, types: Option<String>,
+                           option: impl Into<Option<PostConfigsOption>>)
+                           -> Result<(), Box<dyn Error>> {
+        block_on(self.inner.publish_configs(nacos, content, types, option))
+    }
+
+    pub fn upload_configs_cas(nacos: &NacosConfig, config: DeployConfig, content: &str, types: Option<String>,
+                              cas_md5: &str, option: impl Into<Option<PostConfigsOption>>)
+                              -> Result<(), Box<dyn Error>> {
+        block_on(crate::NacosConfigApi::upload_configs_cas(nacos, config, content, types, cas_md5, option))
+    }
+
+    pub fn publish_configs_cas(&self, nacos: &NacosConfig, content: &str, types: Option<String>,
+                               cas_md5: &str, option: impl Into<Option<PostConfigsOption>>)
+                               -> Result<(), Box<dyn Error>> {
+        block_on(self.inner.publish_configs_cas(nacos, content, types, cas_md5, option))
+    }
+
+    pub fn modify_configs<F>(&self, nacos: &NacosConfig, types: Option<String>, max_retries: usize, func: F)
+                             -> Result<String, Box<dyn Error>>
+        where F: FnMut(&str) -> String
+    {
+        block_on(self.inner.modify_configs(nacos, types, max_retries, func))
+    }
+
+    pub fn delete_configs(nacos: &NacosConfig, config: DeployConfig) -> Result<(), Box<dyn Error>> {
+        block_on(crate::NacosConfigApi::delete_configs(nacos, config))
+    }
+
+    pub fn upload_configs_beta(nacos: &NacosConfig, config: DeployConfig, content: &str, types: Option<String>,
+                               beta_ips: &[String], option: impl Into<Option<PostConfigsOption>>)
+                               -> Result<(), Box<dyn Error>> {
+        block_on(crate::NacosConfigApi::upload_configs_beta(nacos, config, content, types, beta_ips, option))
+    }
+
+    pub fn get_configs_beta(&self, nacos: &NacosConfig) -> Result<Option<BetaConfigInfo>, Box<dyn Error>> {
+        block_on(self.inner.get_configs_beta(nacos))
+    }
+
+    pub fn delete_configs_beta(nacos: &NacosConfig, config: DeployConfig) -> Result<(), Box<dyn Error>> {
+        block_on(crate::NacosConfigApi::delete_configs_beta(nacos, config))
+    }
+
+    pub fn get_history(&self, nacos: &NacosConfig, page_no: i32, page_size: i32)
+                       -> Result<NacosPage<ConfigHistory>, Box<dyn Error>> {
+        block_on(self.inner.get_history(nacos, page_no, page_size))
+    }
+
+    pub fn get_history_detail(&self, nacos: &NacosConfig, nid: i64) -> Result<ConfigHistory, Box<dyn Error>> {
+        block_on(self.inner.get_history_detail(nacos, nid))
+    }
+
+    pub fn get_previous_history(&self, nacos: &NacosConfig, id: i64) -> Result<ConfigHistory, Box<dyn Error>> {
+        block_on(self.inner.get_previous_history(nacos, id))
+    }
+
+    pub fn rollback_to(&self, nacos: &NacosConfig, nid: i64) -> Result<(), Box<dyn Error>> {
+        block_on(self.inner.rollback_to(nacos, nid))
+    }
+
+    pub fn search(nacos: &NacosConfig, mode: SearchMode, page_no: i32, page_size: i32, option: impl Into<Option<SearchConfigsOption>>)
+                  -> Result<NacosPage<ConfigInfo>, Box<dyn Error>> {
+        block_on(crate::NacosConfigApi::search(nacos, mode, page_no, page_size, option))
+    }
+
+    /// 逐页搜索所有匹配的配置 , 返回按需请求下一页的迭代器
+    /// search all matching configs page by page , return an iterator requesting the next page only when needed .
+    pub fn search_all(nacos: &NacosConfig, mode: SearchMode, page_size: i32,
+                      option: impl Into<Option<SearchConfigsOption>>) -> ConfigSearch {
+        ConfigSearch { inner: crate::NacosConfigApi::search_all(nacos, mode, page_size, option) }
+    }
+
+    pub fn export_configs(nacos: &NacosConfig, option: impl Into<Option<ExportConfigsOption>>)
+                          -> Result<Vec<u8>, Box<dyn Error>> {
+        block_on(crate::NacosConfigApi::export_configs(nacos, option))
+    }
+
+    pub fn import_configs(nacos: &NacosConfig, namespace: Option<&str>, policy: SameConfigPolicy, zip: Vec<u8>)
+                          -> Result<ConfigImportSummary, Box<dyn Error>> {
+        block_on(crate::NacosConfigApi::import_configs(nacos, namespace, policy, zip))
+    }
+
+    pub fn clone_configs(nacos: &NacosConfig, target_namespace: Option<&str>, policy: SameConfigPolicy, configs: &[ConfigInfo])
+                         -> Result<ConfigImportSummary, Box<dyn Error>> {
+        block_on(crate::NacosConfigApi::clone_configs(nacos, target_namespace, policy, configs))
+    }
+
+    pub fn clone_namespace(nacos: &NacosConfig, source_namespace: Option<&str>, target_namespace: Option<&str>,
+                           policy: SameConfigPolicy)
+                           -> Result<ConfigImportSummary, Box<dyn Error>> {
+        block_on(crate::NacosConfigApi::clone_namespace(nacos, source_namespace, target_namespace, policy))
+    }
+}
+
+/// [nonblocking::ConfigSearch] 的同步版本 , 以迭代器逐个返回配置
+/// the synchronous version of [nonblocking::ConfigSearch] , which iterates the configs one by one .
+pub struct ConfigSearch {
+    inner: nonblocking::ConfigSearch,
+}
+
+impl ConfigSearch {
+    /// 下一页配置 , 所有页遍历完后返回 None
+    pub fn next_page(&mut self) -> Option<Result<Vec<ConfigInfo>, NacosError>> {
+        block_on(self.inner.next_page())
+    }
+}
+
+impl Iterator for ConfigSearch {
+    type Item = Result<ConfigInfo, NacosError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        block_on(self.inner.next())
+    }
+}
+
+/// [crate::NacosConfigClient] 的同步版本 , 配置监听在共享运行时的后台线程上执行
+/// the synchronous version of [crate::NacosConfigClient] , the configs are listened on the background threads
+/// of the shared runtime .
+/// ```rust,no_run
+/// use nacos_api::NacosConfig;
+/// use nacos_api::blocking::NacosConfigClient;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Orders { max_items: u32 }
+///
+/// let nacos_config = NacosConfig::new("http", "192.168.0.132", 8848);
+/// let client = NacosConfigClient::new("orders.yaml", "DEFAULT_GROUP", None);
+/// let orders: Orders = client.get_as(&nacos_config).unwrap();
+/// for change in client.watch(&nacos_config) {
+///     println!(" perceive the configs changed to > {}", change.new_content)
+/// }
+/// ```
+#[derive(Clone)]
+pub struct NacosConfigClient {
+    inner: nonblocking_client::NacosConfigClient,
+    config_api: NacosConfigApi,
+}
+
+impl NacosConfigClient {
+    pub fn new(data_id: &str, group: &str, tenant: Option<String>) -> Self
+    { Self::from(NacosConfigApi::new(DeployConfig::new(data_id, group, tenant))) }
+    pub fn from(config_api: NacosConfigApi) -> Self {
+        Self { inner: nonblocking_client::NacosConfigClient::from(config_api.inner.clone()), config_api }
+    }
+    pub fn config_api(&self) -> &NacosConfigApi { &self.config_api }
+    /// 固定配置格式 , 不再按配置 type 或 dataId 扩展名识别
+    pub fn with_format(mut self, format: ConfigFormat) -> Self { self.inner = self.inner.with_format(format); self }
+}
+
+impl NacosConfigClient {
+    /// 后台每隔 [interval_secs] 秒拉取配置 如果改变 则将改变后的配置返回给[func]
+    pub fn listen_config<F>(&self, nacos_config: &NacosConfig, func: F, interval_secs: u64) -> BackgroundTask
+        where F: Fn(&String) + Send + 'static
+    {
+        block_on(self.inner.listen_config(nacos_config, func, interval_secs)).into()
+    }
+
+    /// 后台长轮询监听配置 , 立即返回一个阻塞的配置变更迭代器
+    /// long polling listen configs in background and return a blocking iterator of changes immediately .
+    pub fn watch(&self, nacos_config: &NacosConfig) -> ConfigWatch {
+        ConfigWatch { inner: enter(|| self.inner.watch(nacos_config)) }
+    }
+
+    pub fn get_as<T: DeserializeOwned>(&self, nacos_config: &NacosConfig) -> Result<T, NacosError> {
+        block_on(self.inner.get_as(nacos_config))
+    }
+
+    pub fn format_of(&self, config_type: Option<&str>) -> ConfigFormat {
+        self.inner.format_of(config_type)
+    }
+
+    /// 后台监听配置并按其格式反序列化 , 解析失败时推送错误 且不替换最新的有效值
+    pub fn watch_as<T>(&self, nacos_config: &NacosConfig) -> TypedConfigWatch<T>
+        where T: DeserializeOwned + Send + Sync + 'static
+    {
+        TypedConfigWatch { inner: enter(|| self.inner.watch_as(nacos_config)) }
+    }
+
+    /// 获取配置并持续热更新 , 见 [crate::NacosConfigClient::live]
+    pub fn live<T>(&self, nacos_config: &NacosConfig) -> Result<LiveConfig<T>, NacosError>
+        where T: DeserializeOwned + Send + Sync + 'static
+    {
+        block_on(self.inner.live(nacos_config))
+    }
+
+    pub fn live_with<T, F>(&self, nacos_config: &NacosConfig, validate: F) -> Result<LiveConfig<T>, NacosError>
+        where T: DeserializeOwned + Send + Sync + 'static,
+              F: Fn(&T) -> Result<(), String> + Send + Sync + 'static
+    {
+        block_on(self.inner.live_with(nacos_config, validate))
+    }
+
+    pub fn fixed<T>(&self, nacos_config: &NacosConfig) -> Result<LiveConfig<T>, NacosError>
+        where T: DeserializeOwned + Send + Sync + 'static
+    {
+        block_on(self.inner.fixed(nacos_config))
+    }
+}
+
+/// [crate::ConfigWatch] 的同步版本 , 迭代时阻塞等待下一次配置变更
+/// the synchronous version of [crate::ConfigWatch] , which blocks for the next change when iterated .
+pub struct ConfigWatch {
+    inner: nonblocking_client::ConfigWatch,
+}
+
+impl ConfigWatch {
+    /// 阻塞等待下一次配置变更
+    pub fn recv(&mut self) -> Option<ConfigChange> {
+        block_on(self.inner.recv())
+    }
+
+    /// 最多等待 [timeout] , 超时返回 None
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Option<ConfigChange> {
+        block_on(async { time::timeout(timeout, self.inner.recv()).await.ok().flatten() })
+    }
+
+    /// 订阅最新的配置内容 , 可以用 `borrow` 同步读取
+    pub fn latest(&self) -> watch::Receiver<String> {
+        self.inner.latest()
+    }
+
+    pub fn into_latest(self) -> watch::Receiver<String> {
+        self.inner.into_latest()
+    }
+}
+
+impl Iterator for ConfigWatch {
+    type Item = ConfigChange;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.recv()
+    }
+}
+
+/// [crate::TypedConfigWatch] 的同步版本
+/// the synchronous version of [crate::TypedConfigWatch] .
+pub struct TypedConfigWatch<T> {
+    inner: nonblocking_client::TypedConfigWatch<T>,
+}
+
+impl<T> TypedConfigWatch<T> {
+    /// 阻塞等待下一次配置变更的解析结果
+    pub fn recv(&mut self) -> Option<Result<Arc<T>, NacosError>> {
+        block_on(self.inner.recv())
+    }
+
+    /// 最多等待 [timeout] , 超时返回 None
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Option<Result<Arc<T>, NacosError>> {
+        block_on(async { time::timeout(timeout, self.inner.recv()).await.ok().flatten() })
+    }
+
+    /// 订阅最新的有效值 , 首次解析成功前为 None
+    pub fn latest(&self) -> watch::Receiver<Option<Arc<T>>> {
+        self.inner.latest()
+    }
+
+    pub fn into_latest(self) -> watch::Receiver<Option<Arc<T>>> {
+        self.inner.into_latest()
+    }
+}
+
+impl<T> Iterator for TypedConfigWatch<T> {
+    type Item = Result<Arc<T>, NacosError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.recv()
+    }
+}
@@ -0,0 +1,55 @@
+use lazy_static::lazy_static;
+use std::future::Future;
+use tokio::runtime::{Builder, Runtime};
+use tokio::task::JoinHandle;
+
+pub mod config;
+pub mod service;
+
+pub use config::{ConfigSearch, ConfigWatch, NacosConfigApi, NacosConfigClient, TypedConfigWatch};
+pub use service::{NacosClient, NacosRegistration, NacosServiceApi, StatusWatch};
+
+lazy_static! {
+    /// 同步接口共享的后台运行时 , 首次使用时创建 ; 心跳与配置监听等后台任务在其工作线程上运行
+    static ref RUNTIME: Runtime = Builder::new_multi_thread()
+        .worker_threads(2)
+        .thread_name("nacos-blocking")
+        .enable_all()
+        .build()
+        .expect("failed to build the nacos blocking runtime");
+}
+
+/// 在共享运行时上阻塞执行 [future] ;
+/// 不能在异步上下文中调用 ( 如 tokio 运行时的任务内 ) , 否则 panic
+/// block on [future] with the shared runtime .
+/// it can not be called in an async context ( such as a task of a tokio runtime ) , or it panics .
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    RUNTIME.block_on(future)
+}
+
+/// 在共享运行时的上下文中执行 [func] , 使其中启动的后台任务在共享运行时上运行
+pub(crate) fn enter<R>(func: impl FnOnce() -> R) -> R {
+    let _guard = RUNTIME.enter();
+    func()
+}
+
+/// 共享运行时上的后台任务 , drop 时不会中止任务
+/// a background task on the shared runtime , which is not aborted when dropped .
+pub struct BackgroundTask {
+    handle: JoinHandle<()>,
+}
+
+impl From<JoinHandle<()>> for BackgroundTask {
+    fn from(handle: JoinHandle<()>) -> Self { Self { handle } }
+}
+
+impl BackgroundTask {
+    /// 中止后台任务
+    pub fn abort(&self) {
+        self.handle.abort()
+    }
+    /// 后台任务是否已经结束 (被中止)
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+}
@@ -0,0 +1,267 @@
+use crate::blocking::{block_on, enter, BackgroundTask};
+use crate::integration::beat::HeartbeatStatus;
+use crate::integration::service as nonblocking;
+use crate::model::{NacosConfig, ServerConfig};
+use crate::model::err::NacosError;
+use crate::model::service_dto::{RegisterInstanceOption, RemoveInstanceOption, UpdateInstanceOption, UpdateHealthOption, GetInstanceOption, PostServiceOption, DeleteServiceOption, PutServiceOption, GetServiceOption};
+use crate::model::service_vo::{NacosServerView, NacosHost, NacosBeat, NacosServiceInfo, NacosServiceList, NacosMetrics, NacosServerSimpleView};
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task;
+use tokio::time;
+
+/// [crate::NacosServiceApi] 的同步版本
+/// the synchronous version of [crate::NacosServiceApi] .
+#[derive(Clone)]
+pub struct NacosServiceApi {
+    inner: crate::NacosServiceApi,
+}
+
+impl From<crate::NacosServiceApi> for NacosServiceApi {
+    fn from(inner: crate::NacosServiceApi) -> Self { Self { inner } }
+}
+
+impl NacosServiceApi {
+    pub fn new(config: ServerConfig) -> Self { Self::from(crate::NacosServiceApi::new(config)) }
+    pub fn config(&self) -> &ServerConfig { self.inner.config() }
+    pub fn config_mut(&mut self) -> &mut ServerConfig { self.inner.config_mut() }
+}
+
+impl NacosServiceApi {
+    pub fn register_instance(&self, nacos_config: &NacosConfig, option: impl Into<Option<RegisterInstanceOption>>)
+                             -> Result<(), Box<dyn Error>> {
+        block_on(self.inner.register_instance(nacos_config, option))
+    }
+
+    pub fn remove_instance(&self, nacos_config: &NacosConfig, option: impl Into<Option<RemoveInstanceOption>>)
+                           -> Result<(), Box<dyn Error>> {
+        block_on(self.inner.remove_instance(nacos_config, option))
+    }
+
+    pub fn update_instance(&self, nacos_config: &NacosConfig, option: impl Into<Option<UpdateInstanceOption>>)
+                           -> Result<(), Box<dyn Error>> {
+        block_on(self.inner.update_instance(nacos_config, option))
+    }
+
+    pub fn update_instance_health(&self, nacos_config: &NacosConfig, healthy: bool, option: impl Into<Option<UpdateHealthOption>>)
+                                  -> Result<(), Box<dyn Error>> {
+        block_on(self.inner.update_instance_health(nacos_config, healthy, option))
+    }
+
+    pub fn get_instance_list(nacos_config: &NacosConfig, service_name: &str, option: impl Into<Option<GetInstanceOption>>)
+                             -> Result<NacosServerView, Box<dyn Error>> {
+        block_on(crate::NacosServiceApi::get_instance_list(nacos_config, service_name, option))
+    }
+
+    pub fn get_instance(nacos_config: &NacosConfig, service_name: &str, service_ip: &str, service_port: u16,
+                        option: impl Into<Option<GetInstanceOption>>)
+                        -> Result<NacosHost, Box<dyn Error>> {
+        block_on(crate::NacosServiceApi::get_instance(nacos_config, service_name, service_ip, service_port, option))
+    }
+
+    pub fn get_instance_str(nacos_config: &NacosConfig, service_name: &str, service_ip: &str, service_port: u16,
+                            option: impl Into<Option<GetInstanceOption>>)
+                            -> Result<String, Box<dyn Error>> {
+        block_on(crate::NacosServiceApi::get_instance_str(nacos_config, service_name, service_ip, service_port, option))
+    }
+
+    pub fn hart_beat(&self, nacos_config: &NacosConfig) -> Result<NacosBeat, Box<dyn Error + Send + Sync>> {
+        block_on(self.inner.hart_beat(nacos_config))
+    }
+
+    pub fn hart_beat_weight(&self, nacos_config: &NacosConfig, beat: &str) -> Result<NacosBeat, Box<dyn Error + Send + Sync>> {
+        block_on(self.inner.hart_beat_weight(nacos_config, beat))
+    }
+
+    pub fn create_server(nacos_config: &NacosConfig, service_name: &str, option: impl Into<Option<PostServiceOption>>)
+                         -> Result<(), Box<dyn Error>> {
+        block_on(crate::NacosServiceApi::create_server(nacos_config, service_name, option))
+    }
+
+    pub fn delete_server(nacos_config: &NacosConfig, service_name: &str, option: impl Into<Option<DeleteServiceOption>>)
+                         -> Result<(), Box<dyn Error>> {
+        block_on(crate::NacosServiceApi::delete_server(nacos_config, service_name, option))
+    }
+
+    pub fn update_server(nacos_config: &NacosConfig, service_name: &str, option: impl Into<Option<PutServiceOption>>)
+                         -> Result<(), Box<dyn Error>> {
+        block_on(crate::NacosServiceApi::update_server(nacos_config, service_name, option))
+    }
+
+    pub fn get_server(nacos_config: &NacosConfig, service_name: &str, option: impl Into<Option<GetServiceOption>>)
+                      -> Result<NacosServiceInfo, Box<dyn Error>> {
+        block_on(crate::NacosServiceApi::get_server(nacos_config, service_name, option))
+    }
+
+    pub fn get_server_list(nacos_config: &NacosConfig, page_no: i32, page_size: i32, option: impl Into<Option<GetServiceOption>>)
+                           -> Result<NacosServiceList, Box<dyn Error>> {
+        block_on(crate::NacosServiceApi::get_server_list(nacos_config, page_no, page_size, option))
+    }
+
+    pub fn get_operator_metrics(nacos_config: &NacosConfig) -> Result<NacosMetrics, Box<dyn Error>> {
+        block_on(crate::NacosServiceApi::get_operator_metrics(nacos_config))
+    }
+
+    pub fn get_operator_servers(nacos_config: &NacosConfig) -> Result<NacosServerSimpleView, Box<dyn Error>> {
+        block_on(crate::NacosServiceApi::get_operator_servers(nacos_config))
+    }
+}
+
+/// [crate::NacosClient] 的同步版本 , 心跳在共享运行时的后台线程上发送
+/// the synchronous version of [crate::NacosClient] , the hart beat is sent on the background threads of
+/// the shared runtime .
+/// ```rust,no_run
+/// use nacos_api::{NacosConfig, ServerConfig};
+/// use nacos_api::blocking::NacosClient;
+///
+/// let nacos = NacosConfig::new("http", "192.168.0.132", 8848);
+/// let client = NacosClient::new(&nacos, ServerConfig::new("127.0.0.1", 8080, "test", None, None));
+/// let registration = client.try_register(None).unwrap();
+/// // serve until shutdown
+/// registration.deregister().unwrap();
+/// ```
+#[derive(Clone)]
+pub struct NacosClient {
+    inner: nonblocking::NacosClient,
+}
+
+impl From<nonblocking::NacosClient> for NacosClient {
+    fn from(inner: nonblocking::NacosClient) -> Self { Self { inner } }
+}
+
+impl NacosClient {
+    pub fn new(nacos_config: &NacosConfig, server_config: ServerConfig) -> Self {
+        Self::from(nonblocking::NacosClient::new(nacos_config, server_config))
+    }
+    pub fn nacos_config(&self) -> &NacosConfig {
+        self.inner.nacos_config()
+    }
+    pub fn server_config(&self) -> &ServerConfig {
+        self.inner.service_api().config()
+    }
+}
+
+impl NacosClient {
+    /// 注册当前实例并在后台发送心跳 , 注册失败时 panic
+    pub fn register(&self, option: impl Into<Option<RegisterInstanceOption>>) -> NacosRegistration {
+        let option = option.into();
+        match self.try_register(option) {
+            Ok(registration) => registration,
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    /// 注册当前实例并在后台发送心跳 , 注册失败时返回错误
+    pub fn try_register(&self, option: impl Into<Option<RegisterInstanceOption>>)
+                        -> Result<NacosRegistration, NacosError> {
+        block_on(self.inner.try_register(option)).map(NacosRegistration::from)
+    }
+
+    /// 批量注册多个实例 , 见 [crate::NacosClient::try_register_batch]
+    pub fn try_register_batch(nacos_config: &NacosConfig,
+                              servers: Vec<(ServerConfig, Option<RegisterInstanceOption>)>)
+                              -> Result<NacosRegistration, NacosError> {
+        block_on(nonblocking::NacosClient::try_register_batch(nacos_config, servers)).map(NacosRegistration::from)
+    }
+
+    /// 在后台每隔 [retry_secs] 秒尝试注册直到成功 , 立即返回 ; 实例 ip 为空且无法探测本机地址时返回错误
+    pub fn register_in_background(&self, option: impl Into<Option<RegisterInstanceOption>>, retry_secs: u64)
+                                  -> Result<NacosRegistration, NacosError> {
+        let option = option.into();
+        enter(|| self.inner.register_in_background(option, retry_secs)).map(NacosRegistration::from)
+    }
+
+    pub fn report_health(&self, healthy: bool) -> Result<(), NacosError> {
+        block_on(self.inner.report_health(healthy))
+    }
+
+    /// 后台每隔 [interval_secs] 秒执行一次本地健康检查 [check] 并上报结果 , [check] 在阻塞线程池中执行
+    /// run the local health check [check] every [interval_secs] seconds in background and report the result ,
+    /// [check] runs in the blocking thread pool .
+    pub fn report_health_in_background<F>(&self, check: F, interval_secs: u64) -> BackgroundTask
+        where F: Fn() -> bool + Send + Sync + 'static
+    {
+        let check = Arc::new(check);
+        enter(|| self.inner.report_health_in_background(move || {
+            let check = check.clone();
+            async move { task::spawn_blocking(move || check()).await.unwrap_or(false) }
+        }, interval_secs)).into()
+    }
+
+    pub fn get_addr_simple(&self, server_name: &str) -> Result<String, Box<dyn Error>> {
+        block_on(self.inner.get_addr_simple(server_name))
+    }
+}
+
+/// [crate::NacosRegistration] 的同步版本
+/// the synchronous version of [crate::NacosRegistration] .
+pub struct NacosRegistration {
+    inner: nonblocking::NacosRegistration,
+}
+
+impl From<nonblocking::NacosRegistration> for NacosRegistration {
+    fn from(inner: nonblocking::NacosRegistration) -> Self { Self { inner } }
+}
+
+impl NacosRegistration {
+    /// 订阅心跳状态 , 批量注册时为第一个实例的状态
+    pub fn status(&self) -> StatusWatch {
+        StatusWatch { inner: self.inner.status() }
+    }
+    /// 按注册顺序订阅所有实例的心跳状态
+    pub fn statuses(&self) -> Vec<StatusWatch> {
+        self.inner.statuses().into_iter().map(|inner| StatusWatch { inner }).collect()
+    }
+    pub fn current_status(&self) -> HeartbeatStatus {
+        self.inner.current_status()
+    }
+    pub fn servers(&self) -> Vec<&ServerConfig> {
+        self.inner.servers()
+    }
+
+    /// 后台线程在每次心跳状态改变时回调 [func]
+    pub fn on_status_change<F>(&self, func: F) -> BackgroundTask
+        where F: Fn(&HeartbeatStatus) + Send + 'static
+    {
+        enter(|| self.inner.on_status_change(func)).into()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.inner.is_finished()
+    }
+    pub fn abort(&self) {
+        self.inner.abort()
+    }
+
+    /// 停止心跳并注销所有实例 , 返回第一个注销错误
+    pub fn deregister(self) -> Result<(), NacosError> {
+        block_on(self.inner.deregister())
+    }
+}
+
+/// 心跳状态的同步订阅
+/// the synchronous subscription of the hart beat status .
+pub struct StatusWatch {
+    inner: watch::Receiver<HeartbeatStatus>,
+}
+
+impl StatusWatch {
+    /// 当前的心跳状态
+    pub fn current_status(&self) -> HeartbeatStatus {
+        self.inner.borrow().clone()
+    }
+
+    /// 阻塞等待心跳状态改变 , 返回改变后的状态 ; 心跳任务结束 ( 或不发送心跳的持久化实例 ) 时返回 None
+    pub fn wait_change(&mut self) -> Option<HeartbeatStatus> {
+        block_on(self.inner.changed()).ok()?;
+        Some(self.current_status())
+    }
+
+    /// 最多等待 [timeout] , 超时返回 None
+    pub fn wait_change_timeout(&mut self, timeout: Duration) -> Option<HeartbeatStatus> {
+        block_on(async { time::timeout(timeout, self.inner.changed()).await.ok()?.ok() })?;
+        Some(self.current_status())
+    }
+}
@@ -0,0 +1,176 @@
+use crate::integration::service::NacosClient;
+use crate::api::service::NacosServiceApi;
+use crate::model::service_dto::RegisterInstanceOption;
+use std::time::SystemTime;
+use tokio::sync::watch;
+use tokio::time::{self, Duration, Instant};
+
+/// nacos 默认的心跳间隔 毫秒
+const DEFAULT_BEAT_INTERVAL: u64 = 5000;
+/// 心跳返回该状态码表示服务端已不存在当前实例 需要重新注册
+const RESOURCE_NOT_FOUND: i32 = 20404;
+/// 单次心跳 ( 包括重新注册 ) 的超时
+const BEAT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 当前实例的心跳状态 ,
+/// the hart beat state of current instance .
+#[derive(Debug, Clone)]
+pub struct HeartbeatStatus {
+    /// 实例是否处于注册状态
+    pub registered: bool,
+    /// 最近一次心跳成功的时间
+    pub last_success: Option<SystemTime>,
+    /// 连续失败的心跳次数
+    pub consecutive_failures: u32,
+    /// 服务端要求的心跳间隔 毫秒
+    pub client_beat_interval: u64,
+    /// 是否处于轻量心跳模式
+    pub light_beat_enabled: bool,
+}
+
+impl Default for HeartbeatStatus {
+    fn default() -> Self {
+        Self {
+            registered: false,
+            last_success: None,
+            consecutive_failures: 0,
+            client_beat_interval: DEFAULT_BEAT_INTERVAL,
+            light_beat_enabled: false,
+        }
+    }
+}
+
+impl HeartbeatStatus {
+    pub(crate) fn registered() -> Self {
+        Self { registered: true, ..Self::default() }
+    }
+
+    /// 实例已注册且最近一次心跳成功
+    /// whether the instance is registered and the latest hart beat succeeded .
+    pub fn is_alive(&self) -> bool {
+        self.registered && self.consecutive_failures == 0
+    }
+}
+
+/// 共享心跳调度中的一个实例 ,
+/// one instance of the shared hart beat scheduler .
+pub(crate) struct BeatTarget {
+    client: NacosClient,
+    option: Option<RegisterInstanceOption>,
+    status: watch::Sender<HeartbeatStatus>,
+    beat: Option<String>,
+}
+
+impl BeatTarget {
+    /// 为每个实例创建一个心跳目标 并返回对应的状态订阅 , 重新注册时使用实例各自的注册可选项
+    pub(crate) fn create(instances: &[(NacosClient, Option<RegisterInstanceOption>)], initial: HeartbeatStatus)
+                         -> (Vec<BeatTarget>, Vec<watch::Receiver<HeartbeatStatus>>) {
+        instances.iter().map(|(client, option)| {
+            let (tx, rx) = watch::channel(initial.clone());
+            let target = BeatTarget { client: client.clone(), option: option.clone(), status: tx, beat: None };
+            (target, rx)
+        }).unzip()
+    }
+
+    pub(crate) fn client(&self) -> &NacosClient {
+        &self.client
+    }
+    pub(crate) fn option(&self) -> &Option<RegisterInstanceOption> {
+        &self.option
+    }
+
+    pub(crate) fn mark_registered(&self) {
+        self.status.send_replace(HeartbeatStatus::registered());
+    }
+
+    /// 发送一次心跳 并将结果写入状态 , 返回距下次心跳的等待时长 ;
+    /// 服务端不再认识当前实例时使用注册可选项重新注册
+    async fn beat_once(&mut self) -> Duration {
+        let api = self.client.service_api();
+        let nacos_config = self.client.nacos_config();
+        let br = match &self.beat {
+            None => api.hart_beat(nacos_config).await,
+            Some(bt) => api.hart_beat_weight(nacos_config, bt).await,
+        };
+        match br {
+            Ok(nb) if nb.code == RESOURCE_NOT_FOUND => {
+                println!(" -- [warn] nacos instance not found , re-register");
+                self.status.send_modify(|s| s.registered = false);
+                self.beat = None;
+                match api.register_instance(nacos_config, self.option.clone()).await {
+                    Ok(_) => self.status.send_modify(|s| {
+                        s.registered = true;
+                        s.consecutive_failures = 0;
+                    }),
+                    Err(e) => {
+                        println!(" -- [warn] nacos re-register err : {:?}", e);
+                        self.status.send_modify(|s| s.consecutive_failures += 1);
+                    }
+                }
+            }
+            Ok(nb) => {
+                let config = api.config();
+                //如果重拍 获取信息
+                if !nb.light_beat_enabled {
+                    match NacosServiceApi::get_instance_str(
+                        nacos_config,
+                        config.server_name(),
+                        config.server_ip(),
+                        config.server_port(),
+                        None,
+                    ).await {
+                        Ok(bt) => self.beat = Some(bt),
+                        Err(e) => println!(" -- hart beat query info err : {:?}", e),
+                    };
+                }
+                self.status.send_modify(|s| {
+                    s.registered = true;
+                    s.last_success = Some(SystemTime::now());
+                    s.consecutive_failures = 0;
+                    s.client_beat_interval = nb.client_beat_interval;
+                    s.light_beat_enabled = nb.light_beat_enabled;
+                });
+            }
+            Err(e) => {
+                println!(" -- hart beat err : {:?}", e);
+                self.status.send_modify(|s| s.consecutive_failures += 1);
+            }
+        }
+        self.next_delay()
+    }
+
+    /// 心跳超时 , 记为一次失败
+    fn timed_out(&mut self) -> Duration {
+        println!(" -- [warn] nacos hart beat timeout after {:?}", BEAT_TIMEOUT);
+        self.status.send_modify(|s| s.consecutive_failures += 1);
+        self.next_delay()
+    }
+
+    fn next_delay(&self) -> Duration {
+        let interval = self.status.borrow().client_beat_interval;
+        let delay = if interval > 2 { interval - 2 } else { interval };
+        Duration::from_millis(delay)
+    }
+}
+
+/// 在同一个任务中持续为所有 [targets] 发送心跳 , 每个实例按各自的心跳间隔调度 ;
+/// 单次心跳最多等待 [BEAT_TIMEOUT] , 一个实例的请求挂起不会长时间阻塞其他实例 . 持久化实例不发送心跳
+pub(crate) async fn hart_beat_stay(mut targets: Vec<BeatTarget>) {
+    targets.retain(|target| target.client.service_api().config().ephemeral());
+    let mut due = vec![Instant::now(); targets.len()];
+    loop {
+        for (target, next) in targets.iter_mut().zip(due.iter_mut()) {
+            if *next <= Instant::now() {
+                let delay = match time::timeout(BEAT_TIMEOUT, target.beat_once()).await {
+                    Ok(delay) => delay,
+                    Err(_) => target.timed_out(),
+                };
+                *next = Instant::now() + delay;
+            }
+        }
+        match due.iter().min() {
+            Some(next) => time::sleep_until(*next).await,
+            None => return,
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use crate::{NacosConfigClient, NacosConfig, LiveConfig};
+use crate::model::config_format::ConfigFormat;
+use crate::model::err::NacosError;
+use serde::de::DeserializeOwned;
+
+/// 派生 [NacosConfigBinding] , 见 `nacos_api_macro::NacosConfig`
+pub use nacos_api_macro::NacosConfig;
+
+/// 结构体绑定的 nacos 配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfigBinding {
+    pub data_id: &'static str,
+    pub group: &'static str,
+    /// 命名空间ID , None 为 public
+    pub tenant: Option<&'static str>,
+    /// 配置格式 , None 时按配置 type 或 dataId 扩展名识别
+    pub format: Option<ConfigFormat>,
+    /// 是否随配置变更刷新
+    pub refresh: bool,
+}
+
+impl ConfigBinding {
+    /// 绑定配置的客户端 , [tenant] 为 None 时使用绑定的命名空间
+    pub fn client(&self, tenant: Option<String>) -> NacosConfigClient {
+        let tenant = tenant.or_else(|| self.tenant.map(|t| t.to_string()));
+        let client = NacosConfigClient::new(self.data_id, self.group, tenant);
+        match self.format {
+            Some(format) => client.with_format(format),
+            None => client,
+        }
+    }
+}
+
+/// 绑定到某个 dataId 的配置结构体 , 一般由 `#[derive(NacosConfig)]` 实现
+/// the config struct bound to a dataId , usually implemented by `#[derive(NacosConfig)]` .
+/// ```rust,no_run
+/// use nacos_api::{NacosConfig, LiveConfig};
+/// use nacos_api::integration::binding::NacosConfig;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize, NacosConfig)]
+/// #[nacos(data_id = "orders.yaml", group = "DEFAULT_GROUP", format = "yaml", refresh = true)]
+/// struct Orders { max_items: u32 }
+///
+/// # async fn run() -> Result<(), nacos_api::model::err::NacosError> {
+/// let nacos = NacosConfig::new("http", "192.168.0.132", 8848);
+/// let orders = LiveConfig::<Orders>::bind(&nacos).await?;
+/// // in handlers , always the current value
+/// let max_items = orders.load().max_items;
+/// # Ok(())
+/// # }
+/// ```
+pub trait NacosConfigBinding: DeserializeOwned + Send + Sync + 'static {
+    fn binding() -> ConfigBinding;
+}
+
+impl<T: NacosConfigBinding> LiveConfig<T> {
+    /// 加载 [T] 绑定的配置 , 按绑定的 refresh 决定是否持续热更新
+    /// load the config bound to [T] , and keep it hot reloaded if the binding refreshes .
+    pub async fn bind(nacos_config: &NacosConfig) -> Result<Self, NacosError> {
+        Self::bind_in(nacos_config, None).await
+    }
+
+    /// 同 [bind] , 在命名空间 [tenant] 中加载 , None 时使用绑定的命名空间
+    pub async fn bind_in(nacos_config: &NacosConfig, tenant: Option<String>) -> Result<Self, NacosError> {
+        let binding = T::binding();
+        let client = binding.client(tenant);
+        match binding.refresh {
+            true => client.live(nacos_config).await,
+            false => client.fixed(nacos_config).await,
+        }
+    }
+}
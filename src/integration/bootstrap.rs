@@ -0,0 +1,334 @@
+use crate::{NacosClient, NacosConfigClient, NacosConfigApi, NacosConfig, ServerConfig, DeployConfig};
+use crate::model::err::{NacosError, NacosErrorKind};
+use crate::util::net::{self, IpSelector};
+use serde::Deserialize;
+use std::env;
+use std::path::Path;
+
+/// nacos 服务端地址 , 如 `http://127.0.0.1:8848` , 多个地址以逗号分隔时使用第一个
+pub const NACOS_SERVER_ADDR: &str = "NACOS_SERVER_ADDR";
+pub const NACOS_NAMESPACE: &str = "NACOS_NAMESPACE";
+pub const NACOS_SERVICE_NAME: &str = "NACOS_SERVICE_NAME";
+/// 为空时自动探测本机地址
+pub const NACOS_SERVICE_IP: &str = "NACOS_SERVICE_IP";
+pub const NACOS_SERVICE_PORT: &str = "NACOS_SERVICE_PORT";
+pub const NACOS_SERVICE_GROUP: &str = "NACOS_SERVICE_GROUP";
+/// 监听的 dataId , 以逗号分隔 , `group:dataId` 指定分组
+pub const NACOS_CONFIG_DATA_IDS: &str = "NACOS_CONFIG_DATA_IDS";
+/// 未指定分组的 dataId 所用的分组 , 默认为 DEFAULT_GROUP
+pub const NACOS_CONFIG_GROUP: &str = "NACOS_CONFIG_GROUP";
+/// 客户端尚不支持鉴权登录 , 设置了用户名或密码时读取配置失败 , 而不是以未登录的身份调用
+pub const NACOS_USERNAME: &str = "NACOS_USERNAME";
+pub const NACOS_PASSWORD: &str = "NACOS_PASSWORD";
+
+const DEFAULT_GROUP: &str = "DEFAULT_GROUP";
+const DEFAULT_PORT: u32 = 8848;
+
+/// 由环境变量或 nacos.toml 读取的客户端配置 , 用于统一构造服务与配置客户端
+/// the client settings read from env vars or nacos.toml , used to build the naming and config clients uniformly .
+/// ```toml
+/// server_addr = "http://192.168.0.132:8848"
+/// namespace = "dev"
+///
+/// [service]
+/// name = "orders"
+/// # ip = "10.0.0.3" , detected when absent
+/// port = 8080
+/// cidrs = ["10.0.0.0/8"]
+/// interfaces = ["eth*"]
+///
+/// [[configs]]
+/// data_id = "orders.yaml"
+/// group = "DEFAULT_GROUP"
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct NacosBootstrap {
+    server_addr: String,
+    namespace: Option<String>,
+    service: Option<ServiceBootstrap>,
+    configs: Vec<ConfigBootstrap>,
+}
+
+/// 注册的服务
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ServiceBootstrap {
+    pub name: String,
+    pub ip: Option<String>,
+    pub port: u16,
+    pub group: Option<String>,
+    /// 探测本机地址时的 CIDR 白名单
+    pub cidrs: Vec<String>,
+    /// 探测本机地址时的网卡名模式
+    pub interfaces: Vec<String>,
+}
+
+/// 监听的配置
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConfigBootstrap {
+    pub data_id: String,
+    #[serde(default = "default_group")]
+    pub group: String,
+}
+
+fn default_group() -> String {
+    DEFAULT_GROUP.to_string()
+}
+
+impl NacosBootstrap {
+    /// 由环境变量读取
+    /// ```rust,no_run
+    /// use nacos_api::integration::bootstrap::NacosBootstrap;
+    ///
+    /// # async fn run() -> Result<(), nacos_api::model::err::NacosError> {
+    /// // NACOS_SERVER_ADDR=http://192.168.0.132:8848 NACOS_SERVICE_NAME=orders NACOS_SERVICE_PORT=8080
+    /// // NACOS_CONFIG_DATA_IDS=orders.yaml,shared:common.yaml
+    /// let bootstrap = NacosBootstrap::from_env()?;
+    /// let registration = bootstrap.naming_client()?.try_register(None).await?;
+    /// let configs = bootstrap.config_clients();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_env() -> Result<Self, NacosError> {
+        Self::default().with_env()
+    }
+
+    /// 由 toml 文件读取 , 不读取环境变量 , 需要时再调用 [with_env] 覆盖
+    /// read from the toml file without env vars , call [with_env] to override it if needed .
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, NacosError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e|
+            NacosError::new(NacosErrorKind::NotFound, &format!("{} : {}", path.display(), e)))?;
+        Self::from_toml(&content)
+    }
+
+    pub fn from_toml(content: &str) -> Result<Self, NacosError> {
+        let parse_err = |e: toml::de::Error| NacosError::new(NacosErrorKind::Parse, &format!("nacos.toml : {}", e));
+        let value: toml::Value = toml::from_str(content).map_err(parse_err)?;
+        if value.get("username").is_some() || value.get("password").is_some() {
+            return Err(auth_unsupported("username and password in nacos.toml"));
+        }
+        value.try_into().map_err(parse_err)
+    }
+
+    /// 以已设置的环境变量覆盖当前配置
+    /// override current settings by the env vars which are set .
+    pub fn with_env(self) -> Result<Self, NacosError> {
+        self.with_vars(var)
+    }
+
+    fn with_vars<F: Fn(&str) -> Option<String>>(mut self, var: F) -> Result<Self, NacosError> {
+        if var(NACOS_USERNAME).is_some() || var(NACOS_PASSWORD).is_some() {
+            return Err(auth_unsupported(&format!("{} and {}", NACOS_USERNAME, NACOS_PASSWORD)));
+        }
+        if let Some(addr) = var(NACOS_SERVER_ADDR) { self.server_addr = addr; }
+        if let Some(namespace) = var(NACOS_NAMESPACE) { self.namespace = Some(namespace); }
+        let name = var(NACOS_SERVICE_NAME);
+        let ip = var(NACOS_SERVICE_IP);
+        let port = var(NACOS_SERVICE_PORT);
+        let group = var(NACOS_SERVICE_GROUP);
+        let cidrs = var(net::NACOS_CLIENT_CIDRS);
+        let interfaces = var(net::NACOS_CLIENT_INTERFACES);
+        if name.is_some() || ip.is_some() || port.is_some() || group.is_some() || cidrs.is_some() || interfaces.is_some() {
+            let service = self.service.get_or_insert_with(ServiceBootstrap::default);
+            if let Some(name) = name { service.name = name; }
+            if let Some(ip) = ip { service.ip = Some(ip); }
+            if let Some(port) = port {
+                service.port = port.trim().parse().map_err(|_|
+                    invalid(&format!("{} must be a port but got {}", NACOS_SERVICE_PORT, port)))?;
+            }
+            if let Some(group) = group { service.group = Some(group); }
+            if let Some(cidrs) = cidrs { service.cidrs = split_list(&cidrs); }
+            if let Some(interfaces) = interfaces { service.interfaces = split_list(&interfaces); }
+        }
+        if let Some(data_ids) = var(NACOS_CONFIG_DATA_IDS) {
+            let group = var(NACOS_CONFIG_GROUP).unwrap_or_else(default_group);
+            self.configs = split_list(&data_ids).iter()
+                .map(|s| match s.split_once(':') {
+                    Some((group, data_id)) => ConfigBootstrap { data_id: data_id.to_string(), group: group.to_string() },
+                    None => ConfigBootstrap { data_id: s.clone(), group: group.clone() },
+                })
+                .collect();
+        }
+        Ok(self)
+    }
+
+    pub fn server_addr(&self) -> &str {
+        &self.server_addr
+    }
+    pub fn namespace(&self) -> &Option<String> {
+        &self.namespace
+    }
+    pub fn service(&self) -> &Option<ServiceBootstrap> {
+        &self.service
+    }
+    pub fn configs(&self) -> &[ConfigBootstrap] {
+        &self.configs
+    }
+}
+
+impl NacosBootstrap {
+    /// 解析服务端地址 , 缺省协议为 http 缺省端口为 8848
+    pub fn nacos_config(&self) -> Result<NacosConfig, NacosError> {
+        let addr = self.server_addr.split(',').map(|s| s.trim()).find(|s| !s.is_empty())
+            .ok_or_else(|| invalid(&format!("{} is required", NACOS_SERVER_ADDR)))?;
+        let (scheme, rest) = addr.split_once("://").unwrap_or(("http", addr));
+        let host = rest.trim_end_matches('/').trim_end_matches("/nacos");
+        let (ip, port) = match host.rsplit_once(':') {
+            Some((ip, port)) => (ip, port.parse().map_err(|_| invalid(&format!("bad server addr {}", addr)))?),
+            None => (host, DEFAULT_PORT),
+        };
+        Ok(NacosConfig::new(scheme, ip, port))
+    }
+
+    /// 注册的服务 , 未指定 ip 时按 CIDR 白名单 网卡名模式 或 [net::NACOS_CLIENT_IP] 选择本机地址 ,
+    /// 没有规则时以到 nacos 服务端的路由探测
+    /// the service to register , select the local ip by the CIDR allowlist , the interface patterns
+    /// or [net::NACOS_CLIENT_IP] if the ip is absent , and detect by the route to nacos server if there are no rules .
+    pub fn server_config(&self) -> Result<ServerConfig, NacosError> {
+        let service = match &self.service {
+            Some(service) if !service.name.is_empty() => service,
+            _ => return Err(invalid(&format!("{} is required", NACOS_SERVICE_NAME))),
+        };
+        if service.port == 0 { return Err(invalid(&format!("{} is required", NACOS_SERVICE_PORT))); }
+        let ip = match service.ip.as_deref().map(|s| s.trim()).filter(|s| !s.is_empty()) {
+            Some(ip) => ip.to_string(),
+            None => {
+                let mut selector = IpSelector::new().route_to(&self.nacos_config()?.route_target());
+                if let Some(ip) = net::client_ip()? { selector = selector.ip(ip); }
+                for cidr in service.cidrs.iter() { selector = selector.allow_cidr(cidr)?; }
+                for pattern in service.interfaces.iter() { selector = selector.interface(pattern); }
+                selector.select()
+                    .ok_or_else(|| invalid(&format!("can not detect local ip , set {}", NACOS_SERVICE_IP)))?
+                    .to_string()
+            }
+        };
+        Ok(ServerConfig::new(&ip, service.port, &service.name, service.group.clone(), self.namespace.clone()))
+    }
+
+    pub fn naming_client(&self) -> Result<NacosClient, NacosError> {
+        Ok(NacosClient::new(&self.nacos_config()?, self.server_config()?))
+    }
+
+    /// 监听的配置 , 命名空间作为 tenant
+    pub fn deploy_configs(&self) -> Vec<DeployConfig> {
+        self.configs.iter()
+            .map(|c| DeployConfig::new(&c.data_id, &c.group, self.namespace.clone()))
+            .collect()
+    }
+
+    pub fn config_clients(&self) -> Vec<NacosConfigClient> {
+        self.deploy_configs().into_iter()
+            .map(|dc| NacosConfigClient::from(NacosConfigApi::new(dc)))
+            .collect()
+    }
+}
+
+fn split_list(list: &str) -> Vec<String> {
+    list.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()).map(|s| s.to_string()).collect()
+}
+
+fn var(key: &str) -> Option<String> {
+    env::var(key).ok().filter(|v| !v.trim().is_empty())
+}
+
+fn invalid(reason: &str) -> NacosError {
+    NacosError::new(NacosErrorKind::Validate, reason)
+}
+
+fn auth_unsupported(source: &str) -> NacosError {
+    invalid(&format!("nacos auth is not supported , remove {} or disable auth on the server", source))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn vars(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let map: HashMap<String, String> = pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        move |key| map.get(key).cloned()
+    }
+
+    fn addr(server_addr: &str) -> Result<(String, String, u32), NacosError> {
+        let bootstrap = NacosBootstrap { server_addr: server_addr.to_string(), ..Default::default() };
+        bootstrap.nacos_config().map(|c| (c.scheme().to_string(), c.nacos_ip().to_string(), c.nacos_port()))
+    }
+
+    const TOML: &str = r#"
+        server_addr = "http://192.168.0.132:8848"
+        namespace = "dev"
+
+        [service]
+        name = "orders"
+        ip = "10.0.0.3"
+        port = 8080
+
+        [[configs]]
+        data_id = "orders.yaml"
+
+        [[configs]]
+        data_id = "common.yaml"
+        group = "shared"
+    "#;
+
+    #[test]
+    fn read_toml() {
+        let bootstrap = NacosBootstrap::from_toml(TOML).unwrap();
+        assert_eq!("http://192.168.0.132:8848", bootstrap.server_addr());
+        let server = bootstrap.server_config().unwrap();
+        assert_eq!(("10.0.0.3", 8080, "orders"), (server.server_ip(), server.server_port(), server.server_name()));
+        let configs: Vec<_> = bootstrap.deploy_configs().iter()
+            .map(|dc| (dc.group().to_string(), dc.data_id().to_string(), dc.tenant().clone()))
+            .collect();
+        assert_eq!(vec![
+            ("DEFAULT_GROUP".to_string(), "orders.yaml".to_string(), Some("dev".to_string())),
+            ("shared".to_string(), "common.yaml".to_string(), Some("dev".to_string())),
+        ], configs);
+
+        assert_eq!(NacosErrorKind::Parse, NacosBootstrap::from_toml("server_addr = 1").unwrap_err().kind());
+        let auth = NacosBootstrap::from_toml("server_addr = \"127.0.0.1\"\nusername = \"nacos\"");
+        assert_eq!(NacosErrorKind::Validate, auth.unwrap_err().kind());
+    }
+
+    #[test]
+    fn env_overrides_toml() {
+        let bootstrap = NacosBootstrap::from_toml(TOML).unwrap().with_vars(vars(&[
+            (NACOS_SERVER_ADDR, "https://nacos.internal"),
+            (NACOS_SERVICE_PORT, "9090"),
+            (NACOS_CONFIG_DATA_IDS, "payments.yaml"),
+            (NACOS_CONFIG_GROUP, "PAY"),
+        ])).unwrap();
+        assert_eq!("https://nacos.internal", bootstrap.server_addr());
+        // 未设置的环境变量保留文件中的值
+        assert_eq!(&Some("dev".to_string()), bootstrap.namespace());
+        let service = bootstrap.service().clone().unwrap();
+        assert_eq!(("orders", Some("10.0.0.3".to_string()), 9090), (service.name.as_str(), service.ip, service.port));
+        assert_eq!(vec![("PAY", "payments.yaml")],
+                   bootstrap.configs().iter().map(|c| (c.group.as_str(), c.data_id.as_str())).collect::<Vec<_>>());
+
+        let bad_port = NacosBootstrap::default().with_vars(vars(&[(NACOS_SERVICE_PORT, "http")]));
+        assert_eq!(NacosErrorKind::Validate, bad_port.unwrap_err().kind());
+        let auth = NacosBootstrap::default().with_vars(vars(&[(NACOS_PASSWORD, "nacos")]));
+        assert_eq!(NacosErrorKind::Validate, auth.unwrap_err().kind());
+    }
+
+    #[test]
+    fn parse_server_addr() {
+        assert_eq!(("http".to_string(), "a".to_string(), 8848), addr("a:8848,b:8848").unwrap());
+        assert_eq!(("https".to_string(), "a".to_string(), 443), addr("https://a:443/nacos").unwrap());
+        assert_eq!(("http".to_string(), "a".to_string(), 8848), addr(" , a").unwrap());
+        assert_eq!(NacosErrorKind::Validate, addr("a:port").unwrap_err().kind());
+        assert_eq!(NacosErrorKind::Validate, addr("").unwrap_err().kind());
+    }
+
+    #[test]
+    fn split_group_and_data_id() {
+        let bootstrap = NacosBootstrap::default()
+            .with_vars(vars(&[(NACOS_CONFIG_DATA_IDS, "orders.yaml, shared:common.yaml,,")]))
+            .unwrap();
+        assert_eq!(vec![("DEFAULT_GROUP", "orders.yaml"), ("shared", "common.yaml")],
+                   bootstrap.configs().iter().map(|c| (c.group.as_str(), c.data_id.as_str())).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,97 @@
+use std::collections::VecDeque;
+use std::future;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use tokio::sync::Notify;
+
+/// 有界的变更队列 , 已满时丢弃最旧的变更 , 使读取慢的一方最终总能读到最新的变更
+pub(crate) fn channel<T>(capacity: usize) -> (ChangeSender<T>, ChangeReceiver<T>) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State { queue: VecDeque::new(), waker: None, sender_alive: true, receiver_alive: true }),
+        receiver_dropped: Notify::new(),
+        capacity,
+    });
+    (ChangeSender { shared: shared.clone() }, ChangeReceiver { shared })
+}
+
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    receiver_dropped: Notify,
+    capacity: usize,
+}
+
+struct State<T> {
+    queue: VecDeque<T>,
+    waker: Option<Waker>,
+    sender_alive: bool,
+    receiver_alive: bool,
+}
+
+pub(crate) struct ChangeSender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> ChangeSender<T> {
+    /// 推送一次变更 , 已满时丢弃最旧的变更 ; 返回接收端是否仍被持有
+    pub(crate) fn push(&self, change: T) -> bool {
+        let mut state = self.shared.state.lock().unwrap();
+        if !state.receiver_alive { return false; }
+        if state.queue.len() >= self.shared.capacity {
+            state.queue.pop_front();
+            println!(" -- [warn] nacos config change stream is full , the oldest change dropped");
+        }
+        state.queue.push_back(change);
+        if let Some(waker) = state.waker.take() { waker.wake(); }
+        true
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        !self.shared.state.lock().unwrap().receiver_alive
+    }
+
+    /// 等待接收端被丢弃
+    pub(crate) async fn closed(&self) {
+        loop {
+            let dropped = self.shared.receiver_dropped.notified();
+            if self.is_closed() { return; }
+            dropped.await;
+        }
+    }
+}
+
+impl<T> Drop for ChangeSender<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.sender_alive = false;
+        if let Some(waker) = state.waker.take() { waker.wake(); }
+    }
+}
+
+pub(crate) struct ChangeReceiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> ChangeReceiver<T> {
+    /// 下一个变更 , 发送端丢弃且队列为空时返回 None
+    pub(crate) async fn recv(&mut self) -> Option<T> {
+        future::poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    pub(crate) fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let mut state = self.shared.state.lock().unwrap();
+        if let Some(change) = state.queue.pop_front() { return Poll::Ready(Some(change)); }
+        if !state.sender_alive { return Poll::Ready(None); }
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl<T> Drop for ChangeReceiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.receiver_alive = false;
+        state.queue.clear();
+        drop(state);
+        self.shared.receiver_dropped.notify_waiters();
+    }
+}
@@ -0,0 +1,271 @@
+use crate::{NacosConfigClient, NacosConfigApi, NacosConfig, DeployConfig};
+use crate::integration::changes;
+use crate::integration::configs::{TypedConfigWatch, CHANGES_CAPACITY};
+use crate::model::config_format::ConfigFormat;
+use crate::model::err::{NacosError, NacosErrorKind};
+use crate::util::properties;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::sync::{mpsc, watch};
+use tokio::task;
+
+/// 由多层配置合并而成的配置 , 各层按格式解析后深度合并 , 后面的层覆盖前面的层 ;
+/// 不存在的层视为空配置
+/// the config composed of layers , every layer is parsed by its format and deep merged ,
+/// the latter layers override the former . an absent layer is treated as empty .
+#[derive(Clone)]
+pub struct CompositeConfig {
+    layers: Vec<NacosConfigClient>,
+}
+
+impl CompositeConfig {
+    /// [layers] 按优先级从低到高排列
+    pub fn new(layers: Vec<DeployConfig>) -> Self {
+        Self::from_clients(layers.into_iter().map(|dc| NacosConfigClient::from(NacosConfigApi::new(dc))).collect())
+    }
+    /// 由已配置的客户端 ( 如加入了过滤器 ) 组成 , 按优先级从低到高排列
+    pub fn from_clients(layers: Vec<NacosConfigClient>) -> Self {
+        Self { layers }
+    }
+    pub fn layers(&self) -> &[NacosConfigClient] {
+        &self.layers
+    }
+}
+
+/// 与 spring cloud alibaba 一致的应用配置层 : `${app}` `${app}.${ext}` `${app}-${profile}.${ext}` ,
+/// 按优先级从低到高排列 , 通常放在 shared-configs 与 extension-configs 之后 ;
+/// 各层固定使用 [ext] 对应的格式 , 没有扩展名的 `${app}` 也按该格式解析
+/// the application layers the same as spring cloud alibaba , ordered from low to high precedence ,
+/// usually placed after the shared-configs and extension-configs .
+/// every layer is parsed by the format of [ext] , the extensionless `${app}` included .
+/// ```rust,no_run
+/// use nacos_api::{NacosConfigClient, NacosConfig};
+/// use nacos_api::integration::composite::{CompositeConfig, application_layers};
+///
+/// # async fn run() -> Result<(), nacos_api::model::err::NacosError> {
+/// let nacos = NacosConfig::new("http", "192.168.0.132", 8848);
+/// let mut layers = vec![
+///     // shared-configs
+///     NacosConfigClient::new("common.yaml", "DEFAULT_GROUP", None),
+///     // extension-configs
+///     NacosConfigClient::new("redis.yaml", "DEFAULT_GROUP", None),
+/// ];
+/// layers.extend(application_layers("orders", Some("prod"), "yaml", "DEFAULT_GROUP", None));
+/// let config = CompositeConfig::from_clients(layers);
+/// let merged = config.get(&nacos).await?;
+/// let mut changes = config.watch(&nacos);
+/// while let Some(merged) = changes.recv().await {
+///     println!("{:?}", merged);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn application_layers(app: &str, profile: Option<&str>, ext: &str, group: &str, tenant: Option<String>)
+                          -> Vec<NacosConfigClient> {
+    let mut data_ids = vec![app.to_string(), format!("{}.{}", app, ext)];
+    if let Some(profile) = profile {
+        data_ids.push(format!("{}-{}.{}", app, profile, ext));
+    }
+    // nacos 中没有设置 type 的配置为 text , `${app}` 又没有扩展名可识别
+    let format = ConfigFormat::from_type(ext);
+    data_ids.iter().map(|data_id| {
+        let layer = NacosConfigClient::new(data_id, group, tenant.clone());
+        match format {
+            Some(format) => layer.with_format(format),
+            None => layer,
+        }
+    }).collect()
+}
+
+/// 将 [layer] 深度合并到 [base] , 对象逐键合并 , 其他值 ( 包括数组 ) 整体覆盖
+/// deep merge [layer] into [base] , objects are merged key by key and other values ( arrays included ) are replaced .
+pub fn merge(base: &mut Value, layer: Value) {
+    match (base, layer) {
+        (Value::Object(base), Value::Object(layer)) => {
+            for (key, value) in layer {
+                match base.get_mut(&key) {
+                    Some(current) => merge(current, value),
+                    None => { base.insert(key, value); }
+                }
+            }
+        }
+        (base, layer) => *base = layer,
+    }
+}
+
+impl CompositeConfig {
+    /// 获取并合并所有层
+    pub async fn get(&self, nacos_config: &NacosConfig) -> Result<Value, NacosError> {
+        let mut trees = Vec::with_capacity(self.layers.len());
+        for layer in self.layers.iter() {
+            trees.push(fetch_layer(layer, nacos_config).await?);
+        }
+        Ok(merged(&trees))
+    }
+
+    /// 获取合并后的配置并反序列化 , 字符串值按目标字段类型解析 ( properties 层的值都是字符串 )
+    /// get the merged config and deserialize it , string values are parsed by the target field type
+    /// ( all values of properties layers are strings ) .
+    pub async fn get_as<T: DeserializeOwned>(&self, nacos_config: &NacosConfig) -> Result<T, NacosError> {
+        deserialize(self.get(nacos_config).await?)
+    }
+
+    /// 后台监听所有层 , 立即推送一次合并结果 , 之后任意一层变更都重新推送合并结果 ;
+    /// 合并结果未改变时不推送 , 某一层解析失败时推送错误并保留该层上一次的有效值
+    /// listen all layers in background , push the merged view at once and re-push it whenever any layer changes .
+    /// nothing is pushed if the merged view is unchanged , a layer which fails to parse pushes an err
+    /// and keeps its latest valid value .
+    pub fn watch(&self, nacos_config: &NacosConfig) -> TypedConfigWatch<Value> {
+        self.watch_as(nacos_config)
+    }
+
+    /// 同 [watch] , 合并结果反序列化为 [T]
+    pub fn watch_as<T>(&self, nacos_config: &NacosConfig) -> TypedConfigWatch<T>
+        where T: DeserializeOwned + Send + Sync + 'static
+    {
+        let (changes_tx, changes) = changes::channel(CHANGES_CAPACITY);
+        let (latest_tx, latest) = watch::channel(None);
+        let (layer_tx, mut layer_rx) = mpsc::unbounded_channel();
+        let forwards: Vec<_> = self.layers.iter().enumerate().map(|(i, layer)| {
+            let mut raw = layer.watch(nacos_config);
+            let layer_tx = layer_tx.clone();
+            task::spawn(async move {
+                while let Some(change) = raw.recv().await {
+                    if layer_tx.send((i, change)).is_err() { return; }
+                }
+            })
+        }).collect();
+        let this = self.clone();
+        let nacos_config = nacos_config.clone();
+        task::spawn(async move {
+            let emit = |tree: &Value| {
+                let parsed = deserialize::<T>(tree.clone()).map(Arc::new);
+                if let Ok(value) = &parsed { latest_tx.send_replace(Some(value.clone())); }
+                changes_tx.push(parsed) || !latest_tx.is_closed()
+            };
+            let mut trees = Vec::with_capacity(this.layers.len());
+            for layer in this.layers.iter() {
+                trees.push(match fetch_layer(layer, &nacos_config).await {
+                    Ok(tree) => tree,
+                    Err(e) => {
+                        changes_tx.push(Err(e));
+                        None
+                    }
+                });
+            }
+            let mut current = merged(&trees);
+            let mut alive = emit(&current);
+            while alive {
+                let (i, change) = tokio::select! {
+                    change = layer_rx.recv() => match change {
+                        Some(change) => change,
+                        None => break,
+                    },
+                    _ = async { changes_tx.closed().await; latest_tx.closed().await } => break,
+                };
+                let format = this.layers[i].format_of(change.config_type.as_deref());
+                trees[i] = match parse_layer(format, &change.data_id, &change.new_content) {
+                    Ok(tree) => tree,
+                    Err(e) => {
+                        println!(" -- [warn] nacos config parse err : {} , data id : {}", e, change.data_id);
+                        alive = changes_tx.push(Err(e)) || !latest_tx.is_closed();
+                        continue;
+                    }
+                };
+                let next = merged(&trees);
+                if next == current { continue; }
+                current = next;
+                alive = emit(&current);
+            }
+            forwards.iter().for_each(|f| f.abort());
+        });
+        TypedConfigWatch::new(changes, latest)
+    }
+}
+
+/// 获取一层配置 , 不存在时返回 None
+async fn fetch_layer(layer: &NacosConfigClient, nacos_config: &NacosConfig) -> Result<Option<Value>, NacosError> {
+    let content = match layer.config_api().get_configs_content(nacos_config).await.map_err(NacosError::from_boxed) {
+        Ok(content) => content,
+        Err(e) if e.kind() == NacosErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let data_id = layer.config_api().deploy_config().data_id();
+    parse_layer(layer.format_of(content.config_type.as_deref()), data_id, &content.content)
+}
+
+/// 解析一层配置 , 空内容视为不存在 , 只接受键值结构的配置
+fn parse_layer(format: ConfigFormat, data_id: &str, content: &str) -> Result<Option<Value>, NacosError> {
+    if content.trim().is_empty() { return Ok(None); }
+    if format == ConfigFormat::Text {
+        let reason = format!("layer {} is text , only key-value configs can be merged", data_id);
+        return Err(NacosError::new(NacosErrorKind::Parse, &reason));
+    }
+    match format.parse::<Value>(content)? {
+        Value::Null => Ok(None),
+        tree @ Value::Object(_) => Ok(Some(tree)),
+        _ => Err(NacosError::new(NacosErrorKind::Parse, &format!("layer {} is not key-value", data_id))),
+    }
+}
+
+fn merged(trees: &[Option<Value>]) -> Value {
+    let mut result = Value::Object(Default::default());
+    for tree in trees.iter().flatten() {
+        merge(&mut result, tree.clone());
+    }
+    result
+}
+
+fn deserialize<T: DeserializeOwned>(tree: Value) -> Result<T, NacosError> {
+    properties::from_value(tree)
+        .map_err(|e| NacosError::new(NacosErrorKind::Parse, &format!("merged config : {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn merge_nested_maps() {
+        let mut base = json!({"db": {"host": "a", "port": 1}, "hosts": ["a", "b"], "name": {"first": "x"}});
+        merge(&mut base, json!({"db": {"port": 2, "pool": {"max": 8}}, "hosts": ["c"], "name": "y"}));
+        assert_eq!(json!({"db": {"host": "a", "port": 2, "pool": {"max": 8}}, "hosts": ["c"], "name": "y"}), base);
+
+        let mut scalar = json!({"db": "a"});
+        merge(&mut scalar, json!({"db": {"host": "b"}}));
+        assert_eq!(json!({"db": {"host": "b"}}), scalar);
+    }
+
+    #[test]
+    fn latter_layers_take_precedence() {
+        let shared = parse_layer(ConfigFormat::Yaml, "common.yaml", "db:\n  host: a\n  port: 1\nlevel: info").unwrap();
+        let app = parse_layer(ConfigFormat::Properties, "orders.properties", "db.port=2\nlevel=debug").unwrap();
+        let profile = parse_layer(ConfigFormat::Json, "orders-prod.json", r#"{"level": "warn"}"#).unwrap();
+        let absent = parse_layer(ConfigFormat::Yaml, "orders.yaml", "  ").unwrap();
+        assert!(absent.is_none());
+        assert_eq!(json!({"db": {"host": "a", "port": "2"}, "level": "warn"}), merged(&[shared.clone(), app.clone(), absent, profile]));
+        assert_eq!(json!({"db": {"host": "a", "port": 1}, "level": "info"}), merged(&[app, shared]));
+
+        assert!(parse_layer(ConfigFormat::Text, "orders", "level").is_err());
+        assert!(parse_layer(ConfigFormat::Yaml, "orders.yaml", "- a").is_err());
+    }
+
+    #[test]
+    fn application_layer_names() {
+        let names = |layers: &[NacosConfigClient]| layers.iter()
+            .map(|layer| layer.config_api().deploy_config().data_id().to_string())
+            .collect::<Vec<_>>();
+        let layers = application_layers("orders", Some("prod"), "yaml", "DEFAULT_GROUP", None);
+        assert_eq!(vec!["orders", "orders.yaml", "orders-prod.yaml"], names(&layers));
+        // nacos 未设置 type 时为 text , 各层仍按 ext 解析
+        assert!(layers.iter().all(|layer| layer.format_of(Some("text")) == ConfigFormat::Yaml));
+        let layers = application_layers("orders", None, "properties", "APP", Some("dev".to_string()));
+        assert_eq!(vec!["orders", "orders.properties"], names(&layers));
+        assert!(layers.iter().all(|layer| {
+            let dc = layer.config_api().deploy_config();
+            dc.group() == "APP" && dc.tenant() == &Some("dev".to_string())
+        }));
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{NacosConfigClient, NacosConfig};
+use crate::model::err::{NacosError, NacosErrorKind};
+use async_trait::async_trait;
+use config::{AsyncSource, ConfigError, Map, Source, Value, ValueKind};
+use std::fmt::{Debug, Formatter};
+
+/// config crate 的配置源 , 每次构建时获取 nacos 配置并按其格式 ( 配置 type 或 dataId 扩展名 ) 解析 ,
+/// 可与文件 环境变量等配置源叠加在同一个 `ConfigBuilder` 中
+/// the source of config crate , which gets the nacos config on every build and parses it by its format
+/// ( the config type or the dataId extension ) , it can be stacked with files , env vars and so on
+/// in one `ConfigBuilder` .
+/// ```rust,no_run
+/// use nacos_api::{NacosConfigClient, NacosConfig};
+/// use nacos_api::integration::config_source::NacosSource;
+/// use config::{Config, Environment, File};
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let nacos = NacosConfig::new("http", "192.168.0.132", 8848);
+/// let source = NacosSource::new(&nacos, NacosConfigClient::new("orders.yaml", "DEFAULT_GROUP", None));
+/// let settings = Config::builder()
+///     .add_source(File::with_name("settings").required(false))
+///     .add_async_source(source.clone())
+///     .add_source(Environment::with_prefix("ORDERS"))
+///     .build()
+///     .await?;
+/// // or fetch once for the sync builder
+/// let settings = Config::builder()
+///     .add_source(source.snapshot().await?)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct NacosSource {
+    nacos: NacosConfig,
+    client: NacosConfigClient,
+    required: bool,
+}
+
+impl NacosSource {
+    pub fn new(nacos: &NacosConfig, client: NacosConfigClient) -> Self {
+        Self { nacos: nacos.clone(), client, required: true }
+    }
+
+    /// 配置不存在时是否报错 , 默认为 true ; 为 false 时视为空配置
+    pub fn required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+
+    /// 获取一次配置 , 返回可用于同步 `ConfigBuilder` 的快照
+    /// get the config once and return a snapshot usable in the sync `ConfigBuilder` .
+    pub async fn snapshot(&self) -> Result<NacosSnapshot, NacosError> {
+        Ok(NacosSnapshot { origin: self.origin(), table: self.fetch().await? })
+    }
+
+    async fn fetch(&self) -> Result<Map<String, Value>, NacosError> {
+        let content = match self.client.config_api().get_configs_content(&self.nacos).await.map_err(NacosError::from_boxed) {
+            Ok(content) => content,
+            Err(e) if e.kind() == NacosErrorKind::NotFound && !self.required => return Ok(Map::new()),
+            Err(e) => return Err(e),
+        };
+        if content.content.trim().is_empty() { return Ok(Map::new()); }
+        let tree: serde_json::Value = self.client.format_of(content.config_type.as_deref()).parse(&content.content)?;
+        let origin = self.origin();
+        match to_value(&origin, tree).kind {
+            ValueKind::Table(table) => Ok(table),
+            ValueKind::Nil => Ok(Map::new()),
+            _ => Err(NacosError::new(NacosErrorKind::Parse, &format!("{} is not key-value", origin))),
+        }
+    }
+
+    fn origin(&self) -> String {
+        let dc = self.client.config_api().deploy_config();
+        format!("nacos:{}/{}", dc.group(), dc.data_id())
+    }
+}
+
+impl Debug for NacosSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NacosSource")
+            .field("nacos", &self.nacos)
+            .field("config", self.client.config_api().deploy_config())
+            .field("required", &self.required)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl AsyncSource for NacosSource {
+    async fn collect(&self) -> Result<Map<String, Value>, ConfigError> {
+        self.fetch().await.map_err(|e| ConfigError::Foreign(Box::new(e)))
+    }
+}
+
+/// 已获取的 nacos 配置快照 , 实现同步的 config crate 配置源
+/// the snapshot of a fetched nacos config , implementing the sync source of config crate .
+#[derive(Debug, Clone)]
+pub struct NacosSnapshot {
+    origin: String,
+    table: Map<String, Value>,
+}
+
+impl NacosSnapshot {
+    /// 快照来源 , 如 `nacos:DEFAULT_GROUP/orders.yaml`
+    pub fn origin(&self) -> &str {
+        &self.origin
+    }
+}
+
+impl Source for NacosSnapshot {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>, ConfigError> {
+        Ok(self.table.clone())
+    }
+}
+
+/// 转换为 config crate 的值 , properties 的字符串值由 config crate 在读取时按类型转换
+fn to_value(origin: &String, tree: serde_json::Value) -> Value {
+    let kind = match tree {
+        serde_json::Value::Null => ValueKind::Nil,
+        serde_json::Value::Bool(b) => ValueKind::Boolean(b),
+        serde_json::Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => ValueKind::I64(i),
+            (_, Some(u)) => ValueKind::U64(u),
+            _ => ValueKind::Float(n.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(s) => ValueKind::String(s),
+        serde_json::Value::Array(items) => ValueKind::Array(items.into_iter().map(|v| to_value(origin, v)).collect()),
+        serde_json::Value::Object(map) => ValueKind::Table(map.into_iter().map(|(k, v)| (k, to_value(origin, v))).collect()),
+    };
+    Value::new(Some(origin), kind)
+}
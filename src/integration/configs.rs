@@ -1,28 +1,48 @@
 use crate::{NacosConfigApi, NacosConfig, DeployConfig};
-use tokio::task;
+use crate::api::config::LONG_POLLING_TIMEOUT;
+use crate::model::config_format::ConfigFormat;
+use crate::model::config_vo::{ConfigChange, ConfigContent};
+use crate::model::err::{NacosError, NacosErrorKind};
+use futures_core::Stream;
+use serde::de::DeserializeOwned;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime};
+use crate::integration::changes::{self, ChangeReceiver, ChangeSender};
+use tokio::sync::watch;
+use tokio::task::{self, JoinHandle};
 use tokio::time;
-use std::time::Duration;
+
+/// 监听出错后的重试间隔 秒
+const WATCH_RETRY_SECS: u64 = 5;
+/// 变更流最多缓存的变更数 , 超出后丢弃最旧的变更 , 最新值的订阅不受影响
+pub(crate) const CHANGES_CAPACITY: usize = 64;
 
 /// NacosClient 是主要的nacos配置中心调用结构 ,
 /// NacosClient is the primary struct to call nacos configs center .
 #[derive(Clone)]
 pub struct NacosConfigClient {
     config_api: NacosConfigApi,
+    format: Option<ConfigFormat>,
 }
 
 impl NacosConfigClient {
     pub fn new(data_id: &str, group: &str, tenant: Option<String>) -> Self
-    { Self { config_api: NacosConfigApi::new(DeployConfig::new(data_id, group, tenant)) } }
-    pub fn from(config_api: NacosConfigApi) -> Self { Self { config_api } }
+    { Self::from(NacosConfigApi::new(DeployConfig::new(data_id, group, tenant))) }
+    pub fn from(config_api: NacosConfigApi) -> Self { Self { config_api, format: None } }
     pub fn config_api(&self) -> &NacosConfigApi { &self.config_api }
+    /// 固定配置格式 , 不再按配置 type 或 dataId 扩展名识别
+    pub fn with_format(mut self, format: ConfigFormat) -> Self { self.format = Some(format); self }
 }
 
 impl NacosConfigClient {
-    /// 后台线程持续监听配置 如果改变 则将改变后的配置返回给[func] ,
+    /// 后台线程每隔 [interval_secs] 秒拉取配置 如果改变 则将改变后的配置返回给[func] ,
     /// keep listen configs in background and return changed configs to [func] .
-    /// ```rust
+    /// ```rust,no_run
     /// use nacos_api::{NacosConfigClient, NacosConfigApi, DeployConfig, NacosConfig};
     ///
+    /// # async fn run() {
     /// let client = NacosConfigClient::new("test_data", "test_grep", None);
     /// let nacos_config = NacosConfig::new("http", "139.155.225.19", 8848);
     /// // listen the nacos configs center
@@ -31,10 +51,9 @@ impl NacosConfigClient {
     ///     |s| { println!(" perceive the configs changed to > {}", s) },
     ///     10
     /// ).await;
-    /// // make the program keep alive
-    /// loop {}
+    /// # }
     /// ```
-    pub async fn listen_config<F>(&self, nacos_config: &NacosConfig, func: F, interval_secs: u64)
+    pub async fn listen_config<F>(&self, nacos_config: &NacosConfig, func: F, interval_secs: u64) -> JoinHandle<()>
         where F: Fn(&String) + Send + 'static
     {
         task::spawn(listen(
@@ -42,7 +61,236 @@ impl NacosConfigClient {
             nacos_config.clone(),
             func,
             interval_secs,
-        )).await.unwrap()
+        ))
+    }
+
+    /// 后台长轮询监听配置 , 立即返回一个配置变更流 ;
+    /// 首次获取到的配置也会作为一次变更 ( old_content 为空 ) 推送
+    /// 变更流最多缓存 64 个未读取的变更 , 只关心最新值时使用 [ConfigWatch::into_latest]
+    /// long polling listen configs in background and return a stream of changes immediately ,
+    /// the first fetched config is also pushed as a change ( with empty old_content ) .
+    /// at most 64 unread changes are buffered , use [ConfigWatch::into_latest] if only the latest value matters .
+    /// ```rust,no_run
+    /// use nacos_api::{NacosConfigClient, NacosConfig};
+    ///
+    /// # async fn run() {
+    /// let client = NacosConfigClient::new("test_data", "test_grep", None);
+    /// let nacos_config = NacosConfig::new("http", "192.168.0.132", 8848);
+    ///
+    /// let mut changes = client.watch(&nacos_config);
+    /// // the latest content for whom only care about the current value
+    /// let latest = changes.latest();
+    /// while let Some(change) = changes.recv().await {
+    ///     println!(" perceive the configs changed to > {}", change.new_content)
+    /// }
+    /// # }
+    /// ```
+    pub fn watch(&self, nacos_config: &NacosConfig) -> ConfigWatch {
+        let (changes_tx, changes) = changes::channel(CHANGES_CAPACITY);
+        let (latest_tx, latest) = watch::channel(String::new());
+        task::spawn(watch_changes(
+            self.config_api.clone(),
+            nacos_config.clone(),
+            changes_tx,
+            latest_tx,
+        ));
+        ConfigWatch { changes, latest }
+    }
+
+    /// 获取配置并按其格式 ( 配置 type 或 dataId 扩展名 ) 反序列化
+    /// get the config and deserialize it by its format ( the config type or the dataId extension ) .
+    /// ```rust,no_run
+    /// use nacos_api::{NacosConfigClient, NacosConfig};
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Orders { max_items: u32 }
+    ///
+    /// # async fn run() {
+    /// let client = NacosConfigClient::new("orders.yaml", "DEFAULT_GROUP", None);
+    /// let nacos_config = NacosConfig::new("http", "192.168.0.132", 8848);
+    /// let orders: Orders = client.get_as(&nacos_config).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn get_as<T: DeserializeOwned>(&self, nacos_config: &NacosConfig) -> Result<T, NacosError> {
+        let content = self.config_api
+            .get_configs_content(nacos_config)
+            .await
+            .map_err(NacosError::from_boxed)?;
+        self.format_of(content.config_type.as_deref()).parse(&content.content)
+    }
+
+    /// 当前配置的格式 , 以 [with_format] 固定的格式优先
+    pub fn format_of(&self, config_type: Option<&str>) -> ConfigFormat {
+        self.format.unwrap_or_else(|| ConfigFormat::detect(config_type, self.config_api.deploy_config().data_id()))
+    }
+
+    /// 后台监听配置并按其格式反序列化 , 解析失败时推送错误 且不替换最新的有效值
+    /// listen configs in background and deserialize them by the format ,
+    /// a parse failure is pushed as an err and never replaces the latest valid value .
+    pub fn watch_as<T>(&self, nacos_config: &NacosConfig) -> TypedConfigWatch<T>
+        where T: DeserializeOwned + Send + Sync + 'static
+    {
+        let mut raw = self.watch(nacos_config);
+        let format = self.format;
+        let (changes_tx, changes) = changes::channel(CHANGES_CAPACITY);
+        let (latest_tx, latest) = watch::channel(None);
+        task::spawn(async move {
+            loop {
+                let change = tokio::select! {
+                    change = raw.recv() => change,
+                    _ = async { changes_tx.closed().await; latest_tx.closed().await } => None,
+                };
+                let change = match change {
+                    Some(change) => change,
+                    None => return,
+                };
+                let parsed = format.unwrap_or_else(|| ConfigFormat::detect(change.config_type.as_deref(), &change.data_id))
+                    .parse::<T>(&change.new_content)
+                    .map(Arc::new);
+                match &parsed {
+                    Ok(value) => { latest_tx.send_replace(Some(value.clone())); }
+                    Err(e) => println!(" -- [warn] nacos config parse err : {} , data id : {}", e, change.data_id),
+                }
+                changes_tx.push(parsed);
+            }
+        });
+        TypedConfigWatch { changes, latest }
+    }
+}
+
+/// 反序列化后的配置变更流 , 同时提供最新有效值的订阅 ; 与 [ConfigWatch] 相同 , 最多缓存 64 个未读取的变更
+/// the stream of deserialized config changes , also provide the subscription of the latest valid value .
+/// same as [ConfigWatch] , at most 64 unread changes are buffered .
+pub struct TypedConfigWatch<T> {
+    changes: ChangeReceiver<Result<Arc<T>, NacosError>>,
+    latest: watch::Receiver<Option<Arc<T>>>,
+}
+
+impl<T> TypedConfigWatch<T> {
+    pub(crate) fn new(changes: ChangeReceiver<Result<Arc<T>, NacosError>>,
+                      latest: watch::Receiver<Option<Arc<T>>>) -> Self {
+        Self { changes, latest }
+    }
+
+    /// 等待下一次配置变更的解析结果
+    pub async fn recv(&mut self) -> Option<Result<Arc<T>, NacosError>> {
+        self.changes.recv().await
+    }
+
+    /// 订阅最新的有效值 , 首次解析成功前为 None
+    pub fn latest(&self) -> watch::Receiver<Option<Arc<T>>> {
+        self.latest.clone()
+    }
+
+    /// 丢弃变更流 只保留最新有效值的订阅
+    pub fn into_latest(self) -> watch::Receiver<Option<Arc<T>>> {
+        self.latest
+    }
+}
+
+impl<T> Stream for TypedConfigWatch<T> {
+    type Item = Result<Arc<T>, NacosError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.changes.poll_recv(cx)
+    }
+}
+
+/// 配置变更流 , 同时提供最新配置的订阅 ; 变更流和所有订阅都被丢弃后后台监听结束 ;
+/// 变更流未被读取时最多缓存 64 个变更 , 超出后丢弃最旧的变更
+/// the stream of config changes , also provide the subscription of the latest config .
+/// the background listening ends after the stream and all subscriptions are dropped .
+/// at most 64 unread changes are buffered , the oldest ones are dropped .
+pub struct ConfigWatch {
+    changes: ChangeReceiver<ConfigChange>,
+    latest: watch::Receiver<String>,
+}
+
+impl ConfigWatch {
+    /// 等待下一次配置变更
+    pub async fn recv(&mut self) -> Option<ConfigChange> {
+        self.changes.recv().await
+    }
+
+    /// 订阅最新的配置内容
+    pub fn latest(&self) -> watch::Receiver<String> {
+        self.latest.clone()
+    }
+
+    /// 丢弃变更流 只保留最新配置的订阅
+    pub fn into_latest(self) -> watch::Receiver<String> {
+        self.latest
+    }
+}
+
+impl Stream for ConfigWatch {
+    type Item = ConfigChange;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.changes.poll_recv(cx)
+    }
+}
+
+async fn watch_changes(config_api: NacosConfigApi,
+                       nacos_config: NacosConfig,
+                       changes: ChangeSender<ConfigChange>,
+                       latest: watch::Sender<String>) {
+    let mut content = String::new();
+    // 与 nacos 一致 不存在的配置 md5 为空 , 已存在的配置首次监听会立即返回
+    let mut content_md5 = String::new();
+    while !(changes.is_closed() && latest.is_closed()) {
+        let changed = match config_api.listen_configs(&nacos_config, &content_md5, LONG_POLLING_TIMEOUT).await {
+            Ok(changed) => changed,
+            Err(e) => {
+                println!(" -- [warn] nacos listen configs err : {} , nacos config : {:?}", e, nacos_config);
+                time::sleep(Duration::from_secs(WATCH_RETRY_SECS)).await;
+                continue;
+            }
+        };
+        if !changed { continue; }
+        let current = match fetch_current(&config_api, &nacos_config).await {
+            Ok(current) => current,
+            Err(e) => {
+                println!(" -- [warn] nacos get configs err : {} , nacos config : {:?}", e, nacos_config);
+                time::sleep(Duration::from_secs(WATCH_RETRY_SECS)).await;
+                continue;
+            }
+        };
+        // 监听比较的是服务端保存内容的 md5 , 过滤器解密后的内容不能用于监听
+        let current_md5 = match &current {
+            Some(current) => current.md5.clone(),
+            None => String::new(),
+        };
+        if current_md5.eq(&content_md5) {
+            time::sleep(Duration::from_secs(1)).await;
+            continue;
+        }
+        let ConfigContent { content: current, config_type, .. } = current
+            .unwrap_or(ConfigContent { content: String::new(), config_type: None, md5: String::new() });
+        let dc = config_api.deploy_config();
+        let change = ConfigChange {
+            data_id: dc.data_id().to_string(),
+            group: dc.group().to_string(),
+            tenant: dc.tenant().clone(),
+            old_content: std::mem::replace(&mut content, current.clone()),
+            new_content: current.clone(),
+            md5: current_md5.clone(),
+            config_type,
+            timestamp: SystemTime::now(),
+        };
+        content_md5 = current_md5;
+        latest.send_replace(current);
+        changes.push(change);
+    }
+}
+
+/// 获取当前配置 , 配置不存在时返回 None
+async fn fetch_current(config_api: &NacosConfigApi, nacos_config: &NacosConfig) -> Result<Option<ConfigContent>, NacosError> {
+    match config_api.get_configs_content(nacos_config).await.map_err(NacosError::from_boxed) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == NacosErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
     }
 }
 
@@ -52,7 +300,7 @@ async fn listen<F>(config_api: NacosConfigApi, nacos_config: NacosConfig, func:
     let prev_conf = match config_api.get_configs(&nacos_config).await {
         Ok(conf) => conf,
         Err(err) => {
-            eprintln!("config_api.get_configs error: {}, nacos_config: {:?}", err, nacos_config);
+            println!(" -- [warn] nacos get configs err : {} , nacos config : {:?}", err, nacos_config);
             "".to_owned()
         }
     };
@@ -63,7 +311,7 @@ async fn listen<F>(config_api: NacosConfigApi, nacos_config: NacosConfig, func:
         let current_conf = match config_api.get_configs(&nacos_config).await {
             Ok(conf) => conf,
             Err(err) => {
-                eprintln!("config_api.get_configs error: {}, nacos_config: {:?}", err, nacos_config);
+                println!(" -- [warn] nacos get configs err : {} , nacos config : {:?}", err, nacos_config);
                 "".to_owned()
             }
         };
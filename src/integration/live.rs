@@ -0,0 +1,105 @@
+use crate::integration::configs::NacosConfigClient;
+use crate::model::NacosConfig;
+use crate::model::err::{NacosError, NacosErrorKind};
+use arc_swap::ArcSwap;
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+use tokio::task::{self, JoinHandle};
+
+/// 新配置的校验函数 , 返回错误原因时不替换当前值
+type Validator<T> = dyn Fn(&T) -> Result<(), String> + Send + Sync;
+
+/// 持有最新反序列化配置的热更新句柄 , [load] 不加锁 ; 所有克隆都被丢弃后停止监听
+/// the hot reloading handle holding the latest deserialized config , [load] never locks .
+/// the listening stops after all clones are dropped .
+pub struct LiveConfig<T> {
+    value: Arc<ArcSwap<T>>,
+    _listener: Arc<ListenerGuard>,
+}
+
+impl<T> Clone for LiveConfig<T> {
+    fn clone(&self) -> Self {
+        Self { value: self.value.clone(), _listener: self._listener.clone() }
+    }
+}
+
+impl<T> LiveConfig<T> {
+    /// 当前的配置值
+    pub fn load(&self) -> Arc<T> {
+        self.value.load_full()
+    }
+}
+
+/// 丢弃时中止后台监听任务 , 不刷新的配置没有监听任务
+struct ListenerGuard(Option<JoinHandle<()>>);
+
+impl Drop for ListenerGuard {
+    fn drop(&mut self) {
+        if let Some(listener) = &self.0 {
+            listener.abort()
+        }
+    }
+}
+
+impl NacosConfigClient {
+    /// 获取配置并持续热更新 , 返回可以在每次请求中无锁读取的句柄
+    /// get the config and keep it hot reloaded , return a handle which can be read without lock on every request .
+    /// ```rust,no_run
+    /// use nacos_api::{NacosConfigClient, NacosConfig};
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Orders { max_items: u32 }
+    ///
+    /// # async fn run() {
+    /// let client = NacosConfigClient::new("orders.yaml", "DEFAULT_GROUP", None);
+    /// let nacos_config = NacosConfig::new("http", "192.168.0.132", 8848);
+    /// let orders = client.live::<Orders>(&nacos_config).await.unwrap();
+    /// // in handlers
+    /// let max_items = orders.load().max_items;
+    /// # }
+    /// ```
+    pub async fn live<T>(&self, nacos_config: &NacosConfig) -> Result<LiveConfig<T>, NacosError>
+        where T: DeserializeOwned + Send + Sync + 'static
+    {
+        self.live_with(nacos_config, |_: &T| Ok(())).await
+    }
+
+    /// 同 [live] , 新配置先经过 [validate] 校验 , 校验失败时保留当前值 ;
+    /// 初始配置校验失败时返回错误
+    /// same as [live] , but every new config is checked by [validate] first and the current value is kept
+    /// if it fails . return the err if the initial config fails .
+    pub async fn live_with<T, F>(&self, nacos_config: &NacosConfig, validate: F) -> Result<LiveConfig<T>, NacosError>
+        where T: DeserializeOwned + Send + Sync + 'static,
+              F: Fn(&T) -> Result<(), String> + Send + Sync + 'static
+    {
+        let validate: Arc<Validator<T>> = Arc::new(validate);
+        let initial: T = self.get_as(nacos_config).await?;
+        validate(&initial).map_err(|reason| NacosError::new(NacosErrorKind::Validate, &reason))?;
+        let value = Arc::new(ArcSwap::from_pointee(initial));
+        let mut changes = self.watch_as::<T>(nacos_config);
+        let swap = value.clone();
+        let data_id = self.config_api().deploy_config().data_id().to_string();
+        let listener = task::spawn(async move {
+            while let Some(change) = changes.recv().await {
+                // 解析错误已在 watch_as 中输出
+                if let Ok(new) = change {
+                    match validate(&new) {
+                        Ok(_) => swap.store(new),
+                        Err(reason) => println!(" -- [warn] nacos config validate err : {} , data id : {}", reason, data_id),
+                    }
+                }
+            }
+        });
+        Ok(LiveConfig { value, _listener: Arc::new(ListenerGuard(Some(listener))) })
+    }
+
+    /// 只获取一次配置 , 返回不再刷新的句柄
+    /// get the config only once and return a handle which is never refreshed .
+    pub async fn fixed<T>(&self, nacos_config: &NacosConfig) -> Result<LiveConfig<T>, NacosError>
+        where T: DeserializeOwned + Send + Sync + 'static
+    {
+        let value: T = self.get_as(nacos_config).await?;
+        Ok(LiveConfig { value: Arc::new(ArcSwap::from_pointee(value)), _listener: Arc::new(ListenerGuard(None)) })
+    }
+}
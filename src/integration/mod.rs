@@ -1,2 +1,10 @@
+pub mod beat;
 pub mod service;
-pub mod configs;
\ No newline at end of file
+pub mod configs;
+pub mod live;
+pub mod binding;
+pub mod composite;
+#[cfg(feature = "config-rs")]
+pub mod config_source;
+pub mod bootstrap;
+pub(crate) mod changes;
@@ -1,10 +1,15 @@
 use crate::model::{NacosConfig, ServerConfig};
 use crate::api::service::NacosServiceApi;
 use crate::model::service_dto::{RegisterInstanceOption, GetInstanceOption};
+use std::future::Future;
+use crate::integration::beat::{self, BeatTarget, HeartbeatStatus};
 use tokio::{task, time};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
 use tokio::time::Duration;
 use std::error::Error;
-use crate::model::err::NacosError;
+use crate::model::err::{NacosError, NacosErrorKind};
+use crate::util::net;
 use rand::Rng;
 
 /// NacosClient 是主要的nacos服务调用结构 ,
@@ -34,55 +39,236 @@ impl NacosClient {
     pub fn service_api_mut(&mut self) -> &mut NacosServiceApi {
         &mut self.service_api
     }
+
+    /// 实例 ip 为空时按 [net::detect_ip] 以到 nacos 服务端的路由探测本机地址 , 无法探测时返回错误
+    fn with_local_ip(&self) -> Result<Self, NacosError> {
+        let mut client = self.clone();
+        if client.service_api.config().server_ip().trim().is_empty() {
+            let ip = net::detect_ip(&client.nacos_config.route_target())?;
+            client.service_api.config_mut().set_server_ip(ip.to_string());
+        }
+        Ok(client)
+    }
 }
 
 impl NacosClient {
-    /// 注册当前实例并自动发送/回应心跳
-    /// register current instance and send/ack hart beat.
-    /// ```rust
+    /// 注册当前实例并自动发送/回应心跳 , 注册失败时 panic
+    /// register current instance and send/ack hart beat , panic if the registration failed .
+    /// ```rust,no_run
     /// use nacos_api::{NacosClient,NacosConfig, ServerConfig};
     ///
+    /// # async fn run() {
     /// // create a client
     /// let nacos = NacosConfig::new("http", "192.168.0.132", 8848);
     /// let client = NacosClient::new(
     ///     &nacos,
-    ///     ServerConfig::new("127.0.0.1", 8080, "test"),
+    ///     ServerConfig::new("127.0.0.1", 8080, "test", None, None),
     /// );
     ///
     /// // register current instance to nacos
     /// // and new thread in background send and ack hart beat
-    /// client.register(&None).await;
+    /// client.register(None).await;
+    /// # }
+    /// ```
+    pub async fn register(&self, option: impl Into<Option<RegisterInstanceOption>>) {
+        let option = option.into();
+        if let Err(e) = self.try_register(option).await { panic!("{:?}", e) };
+    }
+
+    /// 注册当前实例并自动发送/回应心跳 , 注册失败时返回错误 ; 持久化实例不发送心跳 ,
+    /// 实例 ip 为空时探测本机地址
+    /// register current instance and send/ack hart beat , return the err if the registration failed .
+    /// no hart beat is sent for the persistent instance , and the local ip is detected if the instance ip is empty .
+    /// ```rust,no_run
+    /// use nacos_api::{NacosClient,NacosConfig, ServerConfig};
+    ///
+    /// # async fn run() {
+    /// let nacos = NacosConfig::new("http", "192.168.0.132", 8848);
+    /// let client = NacosClient::new(
+    ///     &nacos,
+    ///     ServerConfig::new("127.0.0.1", 8080, "test", None, None),
+    /// );
+    ///
+    /// match client.try_register(None).await {
+    ///     Ok(registration) => println!(" registered , beat running : {}", !registration.is_finished()),
+    ///     Err(e) => eprintln!(" register failed : {}", e),
+    /// }
+    /// # }
     /// ```
-    pub async fn register(&self, option: &Option<RegisterInstanceOption>) {
-        if let Err(e) = self.service_api
-            .register_instance(self.nacos_config(), option)
-            .await { panic!("{:?}", e) };
+    pub async fn try_register(&self, option: impl Into<Option<RegisterInstanceOption>>)
+                              -> Result<NacosRegistration, NacosError> {
+        let option = option.into();
+        let client = self.with_local_ip()?;
+        client.service_api
+            .register_instance(client.nacos_config(), option.clone())
+            .await
+            .map_err(NacosError::from_boxed)?;
         println!(" -- [info] nacos register success");
+        let clients = vec![client.clone()];
+        let (targets, statuses) = BeatTarget::create(&[(client, option)], HeartbeatStatus::registered());
+        let beat_task = task::spawn(beat::hart_beat_stay(targets));
+        Ok(NacosRegistration { beat_task, clients, statuses })
+    }
+
+    /// 批量注册多个实例 (例如同一进程的 http/grpc/metrics 端口) , 每个实例使用各自的注册可选项 ,
+    /// 所有实例共享同一个心跳任务 ; 任一实例注册失败时注销已注册的实例并返回错误
+    /// register several instances at once ( such as the http/grpc/metrics ports of one process ) with
+    /// their own register options , all of them share one hart beat task . if any registration failed ,
+    /// the registered ones are removed and the err is returned .
+    /// ```rust,no_run
+    /// use nacos_api::{NacosClient,NacosConfig, ServerConfig};
+    /// use nacos_api::model::service_dto::RegisterInstanceOption;
+    ///
+    /// # async fn run() {
+    /// let nacos = NacosConfig::new("http", "192.168.0.132", 8848);
+    /// let servers = vec![
+    ///     (ServerConfig::new("127.0.0.1", 8080, "orders-http", None, None), None),
+    ///     (ServerConfig::new("127.0.0.1", 9090, "orders-grpc", None, None),
+    ///      RegisterInstanceOption::builder().weight(2.0).into()),
+    /// ];
+    ///
+    /// let registration = NacosClient::try_register_batch(&nacos, servers).await.unwrap();
+    /// // remove all of them when shutdown
+    /// registration.deregister().await.unwrap();
+    /// # }
+    /// ```
+    pub async fn try_register_batch(nacos_config: &NacosConfig,
+                                    servers: Vec<(ServerConfig, Option<RegisterInstanceOption>)>)
+                                    -> Result<NacosRegistration, NacosError> {
+        if servers.is_empty() {
+            return Err(NacosError::new(NacosErrorKind::Request, "no server config to register"));
+        }
+        let instances = servers.into_iter()
+            .map(|(server, option)| Ok((NacosClient::new(nacos_config, server).with_local_ip()?, option)))
+            .collect::<Result<Vec<(NacosClient, Option<RegisterInstanceOption>)>, NacosError>>()?;
+        let clients: Vec<NacosClient> = instances.iter().map(|(client, _)| client.clone()).collect();
+        for (i, (client, option)) in instances.iter().enumerate() {
+            let result = client.service_api
+                .register_instance(nacos_config, option.clone())
+                .await
+                .map_err(NacosError::from_boxed);
+            if let Err(e) = result {
+                for registered in &clients[..i] {
+                    if let Err(re) = registered.service_api.remove_instance(nacos_config, None).await {
+                        println!(" -- [warn] nacos remove instance err : {}", re);
+                    }
+                }
+                return Err(e);
+            }
+        }
+        println!(" -- [info] nacos register {} instances success", clients.len());
+        let (targets, statuses) = BeatTarget::create(&instances, HeartbeatStatus::registered());
+        let beat_task = task::spawn(beat::hart_beat_stay(targets));
+        Ok(NacosRegistration { beat_task, clients, statuses })
+    }
+
+    /// 在后台每隔 [retry_secs] 秒尝试注册当前实例 直到成功 , 随后自动发送/回应心跳 ;
+    /// 该方法立即返回 , 服务可以在注册完成前开始处理请求
+    /// keep trying to register current instance every [retry_secs] seconds in background until it succeeds ,
+    /// then send/ack hart beat . return immediately so the service can serve before registered ,
+    /// and return the err at once if the instance ip is empty and the local ip can not be detected .
+    /// ```rust,no_run
+    /// use nacos_api::{NacosClient,NacosConfig, ServerConfig};
+    ///
+    /// # async fn run() {
+    /// let nacos = NacosConfig::new("http", "192.168.0.132", 8848);
+    /// let client = NacosClient::new(
+    ///     &nacos,
+    ///     ServerConfig::new("127.0.0.1", 8080, "test", None, None),
+    /// );
+    ///
+    /// let registration = client.register_in_background(None, 5).unwrap();
+    /// // start serving traffic here
+    /// # }
+    /// ```
+    pub fn register_in_background(&self, option: impl Into<Option<RegisterInstanceOption>>, retry_secs: u64)
+                                  -> Result<NacosRegistration, NacosError> {
+        let option = option.into();
+        let client = self.with_local_ip()?;
+        let clients = vec![client.clone()];
+        let (targets, statuses) = BeatTarget::create(&[(client, option)], HeartbeatStatus::default());
+        let beat_task = task::spawn(async move {
+            for target in &targets {
+                let client = target.client();
+                loop {
+                    match client.service_api
+                        .register_instance(client.nacos_config(), target.option().clone())
+                        .await {
+                        Ok(_) => break,
+                        Err(e) => println!(" -- [warn] nacos register err , retry in {}s : {}", retry_secs, e),
+                    }
+                    time::sleep(Duration::from_secs(retry_secs)).await;
+                }
+                println!(" -- [info] nacos register success");
+                target.mark_registered();
+            }
+            beat::hart_beat_stay(targets).await
+        });
+        Ok(NacosRegistration { beat_task, clients, statuses })
+    }
+
+    /// 向 nacos 上报当前实例的健康状态 , 用于不发送心跳的持久化实例
+    /// report the health state of current instance , for the persistent instance which not send hart beat .
+    pub async fn report_health(&self, healthy: bool) -> Result<(), NacosError> {
+        self.service_api
+            .update_instance_health(self.nacos_config(), healthy, None)
+            .await
+            .map_err(NacosError::from_boxed)
+    }
+
+    /// 后台线程每隔 [interval_secs] 秒执行一次本地健康检查 [check] 并上报结果
+    /// run the local health check [check] every [interval_secs] seconds in background and report the result .
+    /// ```rust,no_run
+    /// use nacos_api::{NacosClient,NacosConfig, ServerConfig};
+    ///
+    /// # async fn run() {
+    /// let nacos = NacosConfig::new("http", "192.168.0.132", 8848);
+    /// let mut server = ServerConfig::new("127.0.0.1", 8080, "test", None, None);
+    /// server.set_ephemeral(false);
+    /// let client = NacosClient::new(&nacos, server);
+    ///
+    /// client.try_register(None).await.unwrap();
+    /// client.report_health_in_background(|| async { std::path::Path::new("/tmp/ready").exists() }, 5);
+    /// # }
+    /// ```
+    pub fn report_health_in_background<F, Fut>(&self, check: F, interval_secs: u64) -> JoinHandle<()>
+        where F: Fn() -> Fut + Send + 'static,
+              Fut: Future<Output=bool> + Send
+    {
         let client = self.clone();
-        task::spawn(hart_beat_stay(client));
+        task::spawn(async move {
+            loop {
+                let healthy = check().await;
+                if let Err(e) = client.report_health(healthy).await {
+                    println!(" -- [warn] nacos report health err : {}", e);
+                }
+                time::sleep(Duration::from_secs(interval_secs)).await;
+            }
+        })
     }
 
     /// 随机获取一个健康实例的请求地址
     /// get a random health instance`s request address .
-    /// ```rust
+    /// ```rust,no_run
     /// use nacos_api::{NacosClient,NacosConfig, ServerConfig};
     ///
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
     /// let nacos = NacosConfig::new("http", "192.168.0.132", 8848);
     /// let client = NacosClient::new(
     ///     &nacos,
-    ///     ServerConfig::new("127.0.0.1", 8080, "test"),
+    ///     ServerConfig::new("127.0.0.1", 8080, "test", None, None),
     /// );
     ///
     /// let addr = client.get_addr_simple("test").await?;
-    /// assert!("http://127.0.0.1:8080", addr.as_str());
+    /// assert_eq!("http://127.0.0.1:8080", addr.as_str());
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn get_addr_simple(&self, server_name: &str) -> Result<String, Box<dyn Error>> {
-        let mut option = GetInstanceOption::default();
-        option.set_healthy_only(Some(true));
         let list = NacosServiceApi::get_instance_list(
             self.nacos_config(),
             server_name,
-            &Some(option)).await?;
+            GetInstanceOption::builder().healthy_only(true)).await?;
         let addr = match list.hosts {
             None => {
                 return Err(Box::new(NacosError::throw(" -- err : server have not instance ")));
@@ -98,47 +284,88 @@ impl NacosClient {
     }
 }
 
-async fn hart_beat_stay(client: NacosClient) {
-    let mut beat: Option<String> = None;
-    'hb: loop {
-        let br = match &beat {
-            None => {
-                client.service_api.hart_beat(client.nacos_config()).await
-            }
-            Some(bt) => {
-                client.service_api.hart_beat_weight(client.nacos_config(), &bt).await
-            }
-        };
-        match br {
-            Ok(nb) => {
-                let config = client.service_api.config();
-                //如果重拍 获取信息
-                if !nb.light_beat_enabled {
-                    let bt = match NacosServiceApi::get_instance_str(
-                        &client.nacos_config,
-                        config.server_name(),
-                        config.server_ip(),
-                        config.server_port(),
-                        &None,
-                    ).await {
-                        Ok(beat) => { beat }
-                        Err(e) => {
-                            println!(" -- hart beat query info err : {:?}", e);
-                            break 'hb;
-                        }
-                    };
-                    beat = Some(bt);
-                }
-                // delay
-                let delay = if nb.client_beat_interval > 2
-                { nb.client_beat_interval - 2 } else { nb.client_beat_interval };
-                time::sleep(Duration::from_millis(delay)).await;
+/// 注册句柄 , 持有已注册实例的后台注册/心跳任务
+/// the handle of a registration , holding the background register/hart beat task of the registered instances .
+pub struct NacosRegistration {
+    beat_task: JoinHandle<()>,
+    clients: Vec<NacosClient>,
+    statuses: Vec<watch::Receiver<HeartbeatStatus>>,
+}
+
+impl NacosRegistration {
+    /// 订阅心跳状态 , 每次心跳后更新 ; 批量注册时返回第一个实例的状态
+    /// subscribe the hart beat status , which is updated after every hart beat .
+    /// return the status of the first instance for the batch registration .
+    /// ```rust,no_run
+    /// use nacos_api::{NacosClient,NacosConfig, ServerConfig};
+    ///
+    /// # async fn run() {
+    /// let nacos = NacosConfig::new("http", "192.168.0.132", 8848);
+    /// let client = NacosClient::new(
+    ///     &nacos,
+    ///     ServerConfig::new("127.0.0.1", 8080, "test", None, None),
+    /// );
+    ///
+    /// let registration = client.register_in_background(None, 5).unwrap();
+    /// let status = registration.status();
+    /// // in readiness probe
+    /// let ready = status.borrow().is_alive();
+    /// # }
+    /// ```
+    pub fn status(&self) -> watch::Receiver<HeartbeatStatus> {
+        self.statuses[0].clone()
+    }
+
+    /// 订阅所有实例的心跳状态 , 顺序与注册顺序一致
+    /// subscribe the hart beat status of all instances , in the order of registration .
+    pub fn statuses(&self) -> Vec<watch::Receiver<HeartbeatStatus>> {
+        self.statuses.clone()
+    }
+
+    /// 当前的心跳状态
+    pub fn current_status(&self) -> HeartbeatStatus {
+        self.statuses[0].borrow().clone()
+    }
+
+    /// 当前注册的所有实例
+    pub fn servers(&self) -> Vec<&ServerConfig> {
+        self.clients.iter().map(|c| c.service_api().config()).collect()
+    }
+
+    /// 后台线程在每次心跳状态改变时回调 [func]
+    /// call [func] in background every time the hart beat status changed .
+    pub fn on_status_change<F>(&self, func: F) -> JoinHandle<()>
+        where F: Fn(&HeartbeatStatus) + Send + 'static
+    {
+        let mut status = self.status();
+        task::spawn(async move {
+            while status.changed().await.is_ok() {
+                func(&status.borrow());
             }
-            Err(e) => {
-                println!(" -- hart beat err : {:?}", e);
-                break 'hb;
+        })
+    }
+
+    /// 后台任务是否已经结束 (被中止)
+    pub fn is_finished(&self) -> bool {
+        self.beat_task.is_finished()
+    }
+
+    /// 中止后台注册/心跳任务 , 不会注销实例
+    pub fn abort(&self) {
+        self.beat_task.abort()
+    }
+
+    /// 停止心跳并注销所有实例 , 返回第一个注销错误
+    /// stop hart beat and remove all instances , return the first err of removing .
+    pub async fn deregister(self) -> Result<(), NacosError> {
+        self.beat_task.abort();
+        let mut result = Ok(());
+        for client in &self.clients {
+            if let Err(e) = client.service_api.remove_instance(client.nacos_config(), None).await {
+                println!(" -- [warn] nacos remove instance err : {}", e);
+                if result.is_ok() { result = Err(NacosError::from_boxed(e)); }
             }
         }
+        result
     }
 }
-
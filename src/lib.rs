@@ -2,10 +2,16 @@ pub mod model;
 pub mod api;
 pub mod util;
 pub mod integration;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "blocking")]
+pub mod blocking;
 
-pub use integration::{service::NacosClient, configs::NacosConfigClient};
-pub use model::{NacosConfig, ServerConfig, DeployConfig};
+pub use integration::{service::{NacosClient, NacosRegistration}, beat::HeartbeatStatus, configs::{NacosConfigClient, ConfigWatch, TypedConfigWatch}, live::LiveConfig, composite::CompositeConfig, bootstrap::NacosBootstrap};
+pub use model::{NacosConfig, ServerConfig, DeployConfig, config_format::ConfigFormat};
 pub use api::service::NacosServiceApi;
 pub use api::config::NacosConfigApi;
 
 extern crate lazy_static;
+// 使 Dto 派生宏生成的 `::nacos_api` 路径在本 crate 内也可用
+extern crate self as nacos_api;
@@ -0,0 +1,93 @@
+use crate::model::err::{NacosError, NacosErrorKind};
+use crate::util::properties;
+use serde::de::{DeserializeOwned, IntoDeserializer};
+use std::fmt::{Display, Formatter};
+
+/// 配置内容的格式 , 对应 nacos 配置的 type ,
+/// the format of config content , matching the type of nacos config .
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Json,
+    Yaml,
+    Toml,
+    Properties,
+    /// 纯文本 , 只能反序列化为字符串
+    Text,
+}
+
+impl ConfigFormat {
+    /// 由 nacos 的配置 type 识别格式
+    pub fn from_type(config_type: &str) -> Option<Self> {
+        match config_type.trim().to_ascii_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "toml" => Some(Self::Toml),
+            "properties" => Some(Self::Properties),
+            "text" | "txt" => Some(Self::Text),
+            _ => None,
+        }
+    }
+
+    /// 由 dataId 的扩展名识别格式
+    pub fn from_data_id(data_id: &str) -> Option<Self> {
+        data_id.rsplit_once('.').and_then(|(_, ext)| Self::from_type(ext))
+    }
+
+    /// 优先使用配置 type , type 缺失或为 text 时使用 dataId 扩展名 , 都无法识别时视为纯文本
+    /// detect by the config type first , then by the dataId extension if the type is absent or text ,
+    /// and fallback to text .
+    pub fn detect(config_type: Option<&str>, data_id: &str) -> Self {
+        match config_type.and_then(Self::from_type) {
+            Some(format) if format != Self::Text => format,
+            _ => Self::from_data_id(data_id).unwrap_or(Self::Text),
+        }
+    }
+
+    /// 按当前格式反序列化配置内容
+    pub fn parse<T: DeserializeOwned>(&self, content: &str) -> Result<T, NacosError> {
+        let result = match self {
+            Self::Json => serde_json::from_str(content).map_err(|e| e.to_string()),
+            Self::Yaml => serde_yaml::from_str(content).map_err(|e| e.to_string()),
+            Self::Toml => toml::from_str(content).map_err(|e| e.to_string()),
+            Self::Properties => properties::from_str(content).map_err(|e| e.to_string()),
+            Self::Text => T::deserialize(content.to_string().into_deserializer())
+                .map_err(|e: serde::de::value::Error| e.to_string()),
+        };
+        result.map_err(|reason| NacosError::new(NacosErrorKind::Parse, &format!("{} : {}", self, reason)))
+    }
+}
+
+/// 输出 nacos 的配置 type
+impl Display for ConfigFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let t = match self {
+            Self::Json => "json",
+            Self::Yaml => "yaml",
+            Self::Toml => "toml",
+            Self::Properties => "properties",
+            Self::Text => "text",
+        };
+        f.write_str(t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConfigFormat;
+
+    #[test]
+    fn detect_by_type_first() {
+        assert_eq!(ConfigFormat::Yaml, ConfigFormat::detect(Some("yaml"), "orders.json"));
+        assert_eq!(ConfigFormat::Yaml, ConfigFormat::detect(Some(" YML "), "orders"));
+        assert_eq!(ConfigFormat::Properties, ConfigFormat::detect(Some("properties"), "orders.yaml"));
+    }
+
+    #[test]
+    fn detect_by_data_id_then_text() {
+        assert_eq!(ConfigFormat::Json, ConfigFormat::detect(Some("text"), "orders.json"));
+        assert_eq!(ConfigFormat::Toml, ConfigFormat::detect(Some("html"), "orders.toml"));
+        assert_eq!(ConfigFormat::Properties, ConfigFormat::detect(None, "orders.v1.properties"));
+        assert_eq!(ConfigFormat::Text, ConfigFormat::detect(None, "orders"));
+        assert_eq!(ConfigFormat::Text, ConfigFormat::detect(Some("text"), "orders.ini"));
+    }
+}
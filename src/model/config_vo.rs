@@ -0,0 +1,166 @@
+use serde::{Serialize, Deserialize, Deserializer};
+use std::time::SystemTime;
+
+/// 配置内容及其类型 ,
+/// the content of a config with its type .
+#[derive(Debug, Clone)]
+pub struct ConfigContent {
+    pub content: String,
+    /// nacos 配置的 type , 如 yaml json properties
+    pub config_type: Option<String>,
+    /// 服务端保存内容的 md5 , 经过过滤器解密时与 [content] 的 md5 不同
+    pub md5: String,
+}
+
+/// 一次配置变更 ,
+/// one change of a config .
+#[derive(Debug, Clone)]
+pub struct ConfigChange {
+    pub data_id: String,
+    pub group: String,
+    pub tenant: Option<String>,
+    /// 变更前的内容 , 首次获取时为空
+    pub old_content: String,
+    /// 变更后的内容 , 配置被删除时为空
+    pub new_content: String,
+    /// 服务端保存的变更后内容的 md5
+    pub md5: String,
+    /// 变更后配置的 type
+    pub config_type: Option<String>,
+    /// 感知到变更的时间
+    pub timestamp: SystemTime,
+}
+
+/// nacos 的分页结果
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NacosPage<T> {
+    #[serde(rename = "totalCount")]
+    pub total_count: i64,
+    #[serde(rename = "pageNumber")]
+    pub page_number: i64,
+    #[serde(rename = "pagesAvailable")]
+    pub pages_available: i64,
+    #[serde(rename = "pageItems")]
+    pub page_items: Vec<T>,
+}
+
+/// 搜索得到的配置
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigInfo {
+    #[serde(deserialize_with = "string_or_number")]
+    pub id: i64,
+    #[serde(rename = "dataId")]
+    pub data_id: String,
+    pub group: String,
+    /// 模糊搜索时不返回内容
+    pub content: Option<String>,
+    pub md5: Option<String>,
+    #[serde(rename = "type")]
+    pub config_type: Option<String>,
+    pub tenant: Option<String>,
+    #[serde(rename = "appName")]
+    pub app_name: Option<String>,
+}
+
+/// nacos 包装的响应结果
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NacosResult<T> {
+    pub code: i32,
+    pub message: Option<String>,
+    pub data: Option<T>,
+}
+
+/// 灰度发布中的配置
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BetaConfigInfo {
+    #[serde(deserialize_with = "string_or_number")]
+    pub id: i64,
+    #[serde(rename = "dataId")]
+    pub data_id: String,
+    pub group: String,
+    pub content: Option<String>,
+    pub md5: Option<String>,
+    #[serde(rename = "type")]
+    pub config_type: Option<String>,
+    pub tenant: Option<String>,
+    #[serde(rename = "appName")]
+    pub app_name: Option<String>,
+    /// 灰度的客户端 ip , 多个用 , 分割
+    #[serde(rename = "betaIps")]
+    pub beta_ips: Option<String>,
+}
+
+/// 配置的 dataId 与 group
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ConfigKey {
+    #[serde(rename = "dataId")]
+    pub data_id: String,
+    pub group: String,
+}
+
+/// 导入或克隆配置的结果汇总
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ConfigImportSummary {
+    /// 成功的数量
+    #[serde(rename = "succCount", default)]
+    pub succeeded: i64,
+    /// 跳过的数量
+    #[serde(rename = "skipCount", default)]
+    pub skipped: i64,
+    /// 跳过的配置
+    #[serde(rename = "skipData", default)]
+    pub skipped_items: Vec<ConfigKey>,
+    /// 失败的配置
+    #[serde(rename = "failData", default)]
+    pub failed_items: Vec<ConfigKey>,
+}
+
+/// 配置的一次历史修改
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigHistory {
+    /// 历史记录的 nid
+    #[serde(deserialize_with = "string_or_number")]
+    pub id: i64,
+    #[serde(rename = "lastId", default)]
+    pub last_id: Option<i64>,
+    #[serde(rename = "dataId")]
+    pub data_id: String,
+    pub group: String,
+    pub tenant: Option<String>,
+    #[serde(rename = "appName")]
+    pub app_name: Option<String>,
+    pub md5: Option<String>,
+    /// 列表查询时不返回内容
+    pub content: Option<String>,
+    /// 配置的 type , 旧版本的服务端不返回
+    #[serde(rename = "type", default)]
+    pub config_type: Option<String>,
+    #[serde(rename = "srcIp")]
+    pub src_ip: Option<String>,
+    #[serde(rename = "srcUser")]
+    pub src_user: Option<String>,
+    /// 操作类型 I 新增 U 修改 D 删除
+    #[serde(rename = "opType")]
+    pub op_type: Option<String>,
+    #[serde(rename = "createdTime")]
+    pub created_time: Option<String>,
+    #[serde(rename = "lastModifiedTime")]
+    pub last_modified_time: Option<String>,
+    /// 加密配置的数据密钥
+    #[serde(rename = "encryptedDataKey", default)]
+    pub encrypted_data_key: Option<String>,
+}
+
+/// nacos 将部分 long 类型的 id 序列化为字符串
+fn string_or_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Id {
+        Number(i64),
+        Text(String),
+    }
+    match Id::deserialize(deserializer)? {
+        Id::Number(n) => Ok(n),
+        Id::Text(s) => s.trim().parse().map_err(serde::de::Error::custom),
+    }
+}
@@ -1,24 +1,69 @@
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 
+/// 错误类别 ,
+/// the category of a [NacosError] .
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NacosErrorKind {
+    /// nacos 服务端返回了非预期的结果
+    Server,
+    /// 请求未能送达或响应未能读取
+    Request,
+    /// 请求的资源不存在
+    NotFound,
+    /// 配置内容无法解析
+    Parse,
+    /// 配置内容未通过校验
+    Validate,
+    /// 配置已被他人修改 , 比较并交换失败
+    Conflict,
+}
+
 /// 自定 Nacos 返回的错误对象
-#[allow(unused)]
 #[derive(Debug)]
 pub struct NacosError {
+    kind: NacosErrorKind,
     reason: String,
 }
 
 impl NacosError {
     pub fn throw(reason: &str) -> Self {
+        Self::new(NacosErrorKind::Server, reason)
+    }
+
+    pub fn new(kind: NacosErrorKind, reason: &str) -> Self {
         Self {
+            kind,
             reason: reason.to_string(),
         }
     }
+
+    pub fn kind(&self) -> NacosErrorKind {
+        self.kind
+    }
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+
+    /// 将 api 返回的装箱错误还原为 [NacosError] , 非 nacos 错误视为请求错误
+    pub(crate) fn from_boxed(err: Box<dyn Error>) -> Self {
+        match err.downcast::<NacosError>() {
+            Ok(e) => *e,
+            Err(e) => Self::new(NacosErrorKind::Request, &e.to_string()),
+        }
+    }
 }
 
 impl Display for NacosError {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        write!(f, " - nacos server return err - ")
+        match self.kind {
+            NacosErrorKind::Server => write!(f, " - nacos server return err - {}", self.reason),
+            NacosErrorKind::Request => write!(f, " - nacos request err - {}", self.reason),
+            NacosErrorKind::NotFound => write!(f, " - nacos resource not found - {}", self.reason),
+            NacosErrorKind::Parse => write!(f, " - nacos config parse err - {}", self.reason),
+            NacosErrorKind::Validate => write!(f, " - nacos config validate err - {}", self.reason),
+            NacosErrorKind::Conflict => write!(f, " - nacos config conflict - {}", self.reason),
+        }
     }
 }
 
@@ -0,0 +1,105 @@
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::ops::{Deref, DerefMut};
+
+/// 心跳间隔 毫秒
+pub const HEART_BEAT_INTERVAL: &str = "preserved.heart.beat.interval";
+/// 心跳超时 毫秒 , 超时后实例被标记为不健康
+pub const HEART_BEAT_TIMEOUT: &str = "preserved.heart.beat.timeout";
+/// 实例删除超时 毫秒 , 超时后实例被删除
+pub const IP_DELETE_TIMEOUT: &str = "preserved.ip.delete.timeout";
+/// 实例ID生成器
+pub const INSTANCE_ID_GENERATOR: &str = "preserved.instance.id.generator";
+/// 注册来源
+pub const REGISTER_SOURCE: &str = "preserved.register.source";
+
+/// 实例的扩展信息 , 请求时序列化为 nacos 要求的 JSON 字符串 ,
+/// the metadata of an instance , serialized to the JSON string nacos required when request .
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(transparent)]
+pub struct InstanceMetadata(HashMap<String, String>);
+
+impl InstanceMetadata {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 由任意可序列化为 JSON 对象的值构造 , 非字符串的值保存为其 JSON 文本
+    /// build from any value serialized as a JSON object , non-string values are kept as their JSON text .
+    pub fn from_serialize<T: Serialize>(value: &T) -> Result<Self, serde_json::Error> {
+        let object = match serde_json::to_value(value)? {
+            serde_json::Value::Object(object) => object,
+            other => return Err(serde::ser::Error::custom(
+                format!("metadata must be a JSON object but got {}", other))),
+        };
+        Ok(Self(object.into_iter().map(|(k, v)| match v {
+            serde_json::Value::String(s) => (k, s),
+            other => (k, other.to_string()),
+        }).collect()))
+    }
+
+    pub fn into_inner(self) -> HashMap<String, String> {
+        self.0
+    }
+
+    pub fn heart_beat_interval(&self) -> Option<u64> {
+        self.parse(HEART_BEAT_INTERVAL)
+    }
+    pub fn heart_beat_timeout(&self) -> Option<u64> {
+        self.parse(HEART_BEAT_TIMEOUT)
+    }
+    pub fn ip_delete_timeout(&self) -> Option<u64> {
+        self.parse(IP_DELETE_TIMEOUT)
+    }
+    pub fn instance_id_generator(&self) -> Option<&str> {
+        self.0.get(INSTANCE_ID_GENERATOR).map(|s| s.as_str())
+    }
+    pub fn register_source(&self) -> Option<&str> {
+        self.0.get(REGISTER_SOURCE).map(|s| s.as_str())
+    }
+
+    pub fn set_heart_beat_interval(&mut self, millis: u64) {
+        self.0.insert(HEART_BEAT_INTERVAL.to_string(), millis.to_string());
+    }
+    pub fn set_heart_beat_timeout(&mut self, millis: u64) {
+        self.0.insert(HEART_BEAT_TIMEOUT.to_string(), millis.to_string());
+    }
+    pub fn set_ip_delete_timeout(&mut self, millis: u64) {
+        self.0.insert(IP_DELETE_TIMEOUT.to_string(), millis.to_string());
+    }
+    pub fn set_instance_id_generator(&mut self, generator: &str) {
+        self.0.insert(INSTANCE_ID_GENERATOR.to_string(), generator.to_string());
+    }
+
+    fn parse(&self, key: &str) -> Option<u64> {
+        self.0.get(key).and_then(|s| s.parse().ok())
+    }
+}
+
+impl From<HashMap<String, String>> for InstanceMetadata {
+    fn from(map: HashMap<String, String>) -> Self {
+        Self(map)
+    }
+}
+
+impl Deref for InstanceMetadata {
+    type Target = HashMap<String, String>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for InstanceMetadata {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// 输出为 JSON 字符串 , 供 Dto 映射为请求参数
+impl Display for InstanceMetadata {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let json = serde_json::to_string(&self.0).map_err(|_| std::fmt::Error)?;
+        f.write_str(&json)
+    }
+}
@@ -1,9 +1,22 @@
 use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+use crate::model::err::NacosError;
+use crate::util::net;
+use crate::util::transport::{HttpTransport, DEFAULT_TRANSPORT};
 
+pub mod config_format;
+pub mod config_vo;
 pub mod err;
+pub mod metadata;
 pub mod service_dto;
 pub mod service_vo;
 
+/// 派生 [Dto] , 见 `nacos_api_macro::Dto`
+pub use nacos_api_macro::Dto;
+/// 派生可选项的链式构造器 , 见 `nacos_api_macro::Builder`
+pub use nacos_api_macro::Builder;
+
 /// Dto接口 请求参数结构体实现该特征
 pub trait Dto {
     /// 该方法用于将 dto中的属性装填入map中 也就是定义名称与值映射
@@ -14,11 +27,12 @@ pub(crate) fn catch_mapping<T: Dto>(map: &mut HashMap<String, String>, option: &
     if let Some(s) = option { s.mapping(map); }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct NacosConfig {
     scheme: String,
     nacos_ip: String,
     nacos_port: u32,
+    transport: Arc<dyn HttpTransport>,
 }
 
 impl Default for NacosConfig {
@@ -27,19 +41,38 @@ impl Default for NacosConfig {
             scheme: "http".to_string(),
             nacos_ip: "127.0.0.1".to_string(),
             nacos_port: 8848,
+            transport: DEFAULT_TRANSPORT.clone(),
         }
     }
 }
 
+impl Debug for NacosConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NacosConfig")
+            .field("scheme", &self.scheme)
+            .field("nacos_ip", &self.nacos_ip)
+            .field("nacos_port", &self.nacos_port)
+            .finish()
+    }
+}
+
 impl NacosConfig {
     pub fn new(scheme: &str, nacos_ip: &str, nacos_port: u32) -> Self {
         Self {
             scheme: scheme.to_string(),
             nacos_ip: nacos_ip.to_string(),
             nacos_port,
+            transport: DEFAULT_TRANSPORT.clone(),
         }
     }
 
+    /// 替换发送请求的传输层 , 默认为共享的 [crate::util::transport::ReqwestTransport]
+    /// replace the transport sending requests , the shared [crate::util::transport::ReqwestTransport] by default .
+    pub fn with_transport(mut self, transport: Arc<dyn HttpTransport>) -> Self {
+        self.transport = transport;
+        self
+    }
+
     pub fn exchange(&mut self, ex: Self) -> Self {
         let prev = self.clone();
         self.scheme = ex.scheme;
@@ -48,6 +81,27 @@ impl NacosConfig {
         prev
     }
 
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+    pub fn nacos_ip(&self) -> &str {
+        &self.nacos_ip
+    }
+    pub fn nacos_port(&self) -> u32 {
+        self.nacos_port
+    }
+    pub fn transport(&self) -> &Arc<dyn HttpTransport> {
+        &self.transport
+    }
+
+    /// 探测本机地址时的路由目标 `ip:port`
+    pub(crate) fn route_target(&self) -> String {
+        match self.nacos_ip.contains(':') {
+            true => format!("[{}]:{}", self.nacos_ip, self.nacos_port),
+            false => format!("{}:{}", self.nacos_ip, self.nacos_port),
+        }
+    }
+
     pub fn addr(&self, target: &str) -> String {
         let sub_path = if target.starts_with('/')
         { target.to_string() } else { format!("/{}", target) };
@@ -58,7 +112,7 @@ impl NacosConfig {
     }
 }
 
-#[derive(Default, Debug, Clone)]
+#[derive(Debug, Clone)]
 pub struct ServerConfig {
     server_ip: String,
     server_port: u16,
@@ -68,6 +122,20 @@ pub struct ServerConfig {
     namespace_id: Option<String>,
 }
 
+/// 与 nacos 一致 , 默认注册为临时实例
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            server_ip: String::new(),
+            server_port: 0,
+            server_name: String::new(),
+            ephemeral: true,
+            group_name: None,
+            namespace_id: None,
+        }
+    }
+}
+
 impl ServerConfig {
     pub fn set_server_ip(&mut self, server_ip: String) {
         self.server_ip = server_ip;
@@ -78,6 +146,9 @@ impl ServerConfig {
     pub fn set_server_name(&mut self, server_name: String) {
         self.server_name = server_name;
     }
+    /// 默认为 true , 持久化实例 ( false ) 不发送心跳 , 健康状态通过 [crate::NacosClient::report_health] 上报
+    /// true by default , the persistent instance ( false ) sends no hart beat , report its health by
+    /// [crate::NacosClient::report_health] .
     pub fn set_ephemeral(&mut self, ephemeral: bool) {
         self.ephemeral = ephemeral;
     }
@@ -114,20 +185,36 @@ impl ServerConfig {
             server_ip: server_ip.to_string(),
             server_port,
             server_name: server_name.to_string(),
-            ephemeral: false,
+            ephemeral: true,
             group_name,
             namespace_id
         }
     }
 
+    /// 按 [crate::util::net::detect_ip] 选择本机地址 , 以到 [nacos] 服务端的路由探测 ; 无法选择时返回错误
+    /// select the local ip by [crate::util::net::detect_ip] , detecting by the route to the [nacos] server .
+    /// return the err if no ip can be selected .
+    /// ```rust,no_run
+    /// use nacos_api::{NacosConfig, ServerConfig};
+    ///
+    /// # fn run() -> Result<(), nacos_api::model::err::NacosError> {
+    /// let nacos = NacosConfig::new("http", "192.168.0.132", 8848);
+    /// let server = ServerConfig::detect(&nacos, 8080, "orders", None, None)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn detect(nacos: &NacosConfig, server_port: u16, server_name: &str, group_name: Option<String>,
+                  namespace_id: Option<String>) -> Result<Self, NacosError> {
+        let ip = net::detect_ip(&nacos.route_target())?;
+        Ok(Self::new(&ip.to_string(), server_port, server_name, group_name, namespace_id))
+    }
+
     pub(crate) fn init_map(&self) -> HashMap<String, String> {
         let mut map = HashMap::<String, String>::new();
         map.insert("ip".to_string(), self.server_ip().to_string());
         map.insert("port".to_string(), self.server_port().to_string());
         map.insert("serviceName".to_string(), self.server_name().to_string());
-        if self.ephemeral {
-            map.insert("ephemeral".to_string(), true.to_string());
-        }
+        map.insert("ephemeral".to_string(), self.ephemeral.to_string());
         if let Some(s) = &self.group_name {
             map.insert("groupName".to_string(), s.to_string());
         }
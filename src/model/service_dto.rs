@@ -1,8 +1,8 @@
-use crate::model::Dto;
+use crate::model::metadata::InstanceMetadata;
 use std::collections::HashMap;
-use nacos_api_macro::Dto;
+use nacos_api_macro::{Builder, Dto};
 
-#[derive(Default, Dto)]
+#[derive(Default, Clone, Dto, Builder)]
 /// 注册实例的可选项
 pub struct RegisterInstanceOption {
     /// 命名空间ID
@@ -14,8 +14,9 @@ pub struct RegisterInstanceOption {
     /// 是否健康
     healthy: Option<bool>,
     /// 扩展信息
-    metadata: Option<String>,
+    metadata: Option<InstanceMetadata>,
     /// 集群名
+    #[builder(rename = "cluster")]
     cluster_name: Option<String>,
 }
 
@@ -32,8 +33,8 @@ impl RegisterInstanceOption {
     pub fn set_healthy(&mut self, healthy: Option<bool>) {
         self.healthy = healthy;
     }
-    pub fn set_metadata(&mut self, metadata: Option<String>) {
-        self.metadata = metadata;
+    pub fn set_metadata(&mut self, metadata: Option<HashMap<String, String>>) {
+        self.metadata = metadata.map(InstanceMetadata::from);
     }
     pub fn set_cluster_name(&mut self, cluster_name: Option<String>) {
         self.cluster_name = cluster_name;
@@ -50,24 +51,28 @@ impl RegisterInstanceOption {
     pub fn healthy(&self) -> Option<bool> {
         self.healthy
     }
-    pub fn metadata(&self) -> &Option<String> {
+    pub fn metadata(&self) -> &Option<InstanceMetadata> {
         &self.metadata
     }
+    pub fn metadata_mut(&mut self) -> &mut Option<InstanceMetadata> {
+        &mut self.metadata
+    }
     pub fn cluster_name(&self) -> &Option<String> {
         &self.cluster_name
     }
 }
 
 impl RegisterInstanceOption {
-    pub fn new(namespace_id: Option<String>, weight: Option<f64>, enabled: Option<bool>, healthy: Option<bool>, metadata: Option<String>, cluster_name: Option<String>) -> Self {
-        RegisterInstanceOption { namespace_id, weight, enabled, healthy, metadata, cluster_name }
+    pub fn new(namespace_id: Option<String>, weight: Option<f64>, enabled: Option<bool>, healthy: Option<bool>, metadata: Option<HashMap<String, String>>, cluster_name: Option<String>) -> Self {
+        RegisterInstanceOption { namespace_id, weight, enabled, healthy, metadata: metadata.map(InstanceMetadata::from), cluster_name }
     }
 }
 
 /// 注销实例可选项
-#[derive(Default, Dto)]
+#[derive(Default, Dto, Builder)]
 pub struct RemoveInstanceOption {
     /// 集群名称
+    #[builder(rename = "cluster")]
     cluster_name: Option<String>,
     /// 命名空间ID
     namespace_id: Option<String>,
@@ -88,17 +93,18 @@ impl RemoveInstanceOption {
     }
 }
 
-#[derive(Default, Dto)]
+#[derive(Default, Dto, Builder)]
 /// 修改实例可选项
 pub struct UpdateInstanceOption {
     /// 集群名称
+    #[builder(rename = "cluster")]
     cluster_name: Option<String>,
     /// 命名空间ID
     namespace_id: Option<String>,
     /// 权重
     weight: Option<f64>,
-    /// 扩展信息 JSON
-    metadata: Option<String>,
+    /// 扩展信息
+    metadata: Option<InstanceMetadata>,
     /// 是否打开流量
     enabled: Option<bool>,
 }
@@ -113,8 +119,8 @@ impl UpdateInstanceOption {
     pub fn set_weight(&mut self, weight: Option<f64>) {
         self.weight = weight;
     }
-    pub fn set_metadata(&mut self, metadata: Option<String>) {
-        self.metadata = metadata;
+    pub fn set_metadata(&mut self, metadata: Option<HashMap<String, String>>) {
+        self.metadata = metadata.map(InstanceMetadata::from);
     }
     pub fn set_enabled(&mut self, enabled: Option<bool>) {
         self.enabled = enabled;
@@ -128,15 +134,43 @@ impl UpdateInstanceOption {
     pub fn weight(&self) -> Option<f64> {
         self.weight
     }
-    pub fn metadata(&self) -> &Option<String> {
+    pub fn metadata(&self) -> &Option<InstanceMetadata> {
         &self.metadata
     }
+    pub fn metadata_mut(&mut self) -> &mut Option<InstanceMetadata> {
+        &mut self.metadata
+    }
     pub fn enabled(&self) -> Option<bool> {
         self.enabled
     }
 }
 
-#[derive(Default, Dto)]
+#[derive(Default, Dto, Builder)]
+/// 更新实例健康状态可选项
+pub struct UpdateHealthOption {
+    /// 集群名称
+    #[builder(rename = "cluster")]
+    cluster_name: Option<String>,
+    /// 命名空间ID
+    namespace_id: Option<String>,
+}
+
+impl UpdateHealthOption {
+    pub fn set_cluster_name(&mut self, cluster_name: Option<String>) {
+        self.cluster_name = cluster_name;
+    }
+    pub fn set_namespace_id(&mut self, namespace_id: Option<String>) {
+        self.namespace_id = namespace_id;
+    }
+    pub fn cluster_name(&self) -> &Option<String> {
+        &self.cluster_name
+    }
+    pub fn namespace_id(&self) -> &Option<String> {
+        &self.namespace_id
+    }
+}
+
+#[derive(Default, Dto, Builder)]
 /// 获取实例选项
 pub struct GetInstanceOption {
     /// 命名空间ID
@@ -168,7 +202,7 @@ impl GetInstanceOption {
     }
 }
 
-#[derive(Debug, Default, Dto)]
+#[derive(Debug, Default, Dto, Builder)]
 pub struct PostServiceOption {
     group_name: Option<String>,
     namespace_id: Option<String>,
@@ -210,7 +244,7 @@ impl PostServiceOption {
     }
 }
 
-#[derive(Debug, Default, Dto)]
+#[derive(Debug, Default, Dto, Builder)]
 pub struct DeleteServiceOption {
     group_name: Option<String>,
     namespace_id: Option<String>,
@@ -231,7 +265,7 @@ impl DeleteServiceOption {
     }
 }
 
-#[derive(Debug, Default, Dto)]
+#[derive(Debug, Default, Dto, Builder)]
 pub struct PutServiceOption {
     group_name: Option<String>,
     namespace_id: Option<String>,
@@ -273,7 +307,7 @@ impl PutServiceOption {
     }
 }
 
-#[derive(Debug, Default, Dto)]
+#[derive(Debug, Default, Dto, Builder)]
 pub struct GetServiceOption {
     group_name: Option<String>,
     namespace_id: Option<String>,
@@ -294,7 +328,199 @@ impl GetServiceOption {
     }
 }
 
-#[derive(Debug, Default, Dto)]
+/// 发布配置可选项
+#[derive(Debug, Default, Clone, Dto, Builder)]
 pub struct PostConfigsOption {
+    /// 应用名
+    app_name: Option<String>,
+    /// 发布人
+    #[dto(rename = "src_user")]
+    src_user: Option<String>,
+    /// 配置标签 多个用 , 分割
+    #[dto(rename = "config_tags")]
+    config_tags: Option<String>,
+    /// 描述
+    desc: Option<String>,
+    /// 用途
+    #[dto(rename = "use")]
+    usage: Option<String>,
+    /// 影响
+    effect: Option<String>,
+    /// 配置内容的 schema
+    schema: Option<String>,
+}
 
-}
\ No newline at end of file
+impl PostConfigsOption {
+    pub fn set_app_name(&mut self, app_name: Option<String>) {
+        self.app_name = app_name;
+    }
+    pub fn set_src_user(&mut self, src_user: Option<String>) {
+        self.src_user = src_user;
+    }
+    pub fn set_config_tags(&mut self, config_tags: Option<String>) {
+        self.config_tags = config_tags;
+    }
+    pub fn set_desc(&mut self, desc: Option<String>) {
+        self.desc = desc;
+    }
+    pub fn set_usage(&mut self, usage: Option<String>) {
+        self.usage = usage;
+    }
+    pub fn set_effect(&mut self, effect: Option<String>) {
+        self.effect = effect;
+    }
+    pub fn set_schema(&mut self, schema: Option<String>) {
+        self.schema = schema;
+    }
+    pub fn app_name(&self) -> &Option<String> {
+        &self.app_name
+    }
+    pub fn src_user(&self) -> &Option<String> {
+        &self.src_user
+    }
+    pub fn config_tags(&self) -> &Option<String> {
+        &self.config_tags
+    }
+    pub fn desc(&self) -> &Option<String> {
+        &self.desc
+    }
+    pub fn usage(&self) -> &Option<String> {
+        &self.usage
+    }
+    pub fn effect(&self) -> &Option<String> {
+        &self.effect
+    }
+    pub fn schema(&self) -> &Option<String> {
+        &self.schema
+    }
+}
+
+/// 配置搜索方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchMode {
+    /// 精确匹配 dataId 与 group
+    Accurate,
+    /// 模糊匹配 , dataId 与 group 支持 `*` 通配
+    Blur,
+}
+
+impl SearchMode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            SearchMode::Accurate => "accurate",
+            SearchMode::Blur => "blur",
+        }
+    }
+}
+
+/// 搜索配置可选项
+#[derive(Debug, Default, Clone, Dto, Builder)]
+pub struct SearchConfigsOption {
+    data_id: Option<String>,
+    group: Option<String>,
+    /// 命名空间ID
+    tenant: Option<String>,
+    app_name: Option<String>,
+    /// 配置标签 多个用 , 分割
+    #[dto(rename = "config_tags")]
+    config_tags: Option<String>,
+}
+
+impl SearchConfigsOption {
+    pub fn set_data_id(&mut self, data_id: Option<String>) {
+        self.data_id = data_id;
+    }
+    pub fn set_group(&mut self, group: Option<String>) {
+        self.group = group;
+    }
+    pub fn set_tenant(&mut self, tenant: Option<String>) {
+        self.tenant = tenant;
+    }
+    pub fn set_app_name(&mut self, app_name: Option<String>) {
+        self.app_name = app_name;
+    }
+    pub fn set_config_tags(&mut self, config_tags: Option<String>) {
+        self.config_tags = config_tags;
+    }
+    pub fn data_id(&self) -> &Option<String> {
+        &self.data_id
+    }
+    pub fn group(&self) -> &Option<String> {
+        &self.group
+    }
+    pub fn tenant(&self) -> &Option<String> {
+        &self.tenant
+    }
+    pub fn app_name(&self) -> &Option<String> {
+        &self.app_name
+    }
+    pub fn config_tags(&self) -> &Option<String> {
+        &self.config_tags
+    }
+}
+
+/// 导入或克隆时遇到同名配置的处理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SameConfigPolicy {
+    /// 终止导入
+    Abort,
+    /// 跳过同名配置
+    Skip,
+    /// 覆盖同名配置
+    Overwrite,
+}
+
+impl SameConfigPolicy {
+    pub fn as_str(&self) -> &str {
+        match self {
+            SameConfigPolicy::Abort => "ABORT",
+            SameConfigPolicy::Skip => "SKIP",
+            SameConfigPolicy::Overwrite => "OVERWRITE",
+        }
+    }
+}
+
+/// 导出配置可选项 , 都为空时导出整个命名空间
+#[derive(Debug, Default, Clone, Dto, Builder)]
+pub struct ExportConfigsOption {
+    /// 命名空间ID
+    tenant: Option<String>,
+    data_id: Option<String>,
+    group: Option<String>,
+    app_name: Option<String>,
+    /// 配置的 id 多个用 , 分割
+    ids: Option<String>,
+}
+
+impl ExportConfigsOption {
+    pub fn set_tenant(&mut self, tenant: Option<String>) {
+        self.tenant = tenant;
+    }
+    pub fn set_data_id(&mut self, data_id: Option<String>) {
+        self.data_id = data_id;
+    }
+    pub fn set_group(&mut self, group: Option<String>) {
+        self.group = group;
+    }
+    pub fn set_app_name(&mut self, app_name: Option<String>) {
+        self.app_name = app_name;
+    }
+    pub fn set_ids(&mut self, ids: Option<String>) {
+        self.ids = ids;
+    }
+    pub fn tenant(&self) -> &Option<String> {
+        &self.tenant
+    }
+    pub fn data_id(&self) -> &Option<String> {
+        &self.data_id
+    }
+    pub fn group(&self) -> &Option<String> {
+        &self.group
+    }
+    pub fn app_name(&self) -> &Option<String> {
+        &self.app_name
+    }
+    pub fn ids(&self) -> &Option<String> {
+        &self.ids
+    }
+}
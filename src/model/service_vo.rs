@@ -1,5 +1,6 @@
 use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
+use crate::model::metadata::InstanceMetadata;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct NacosHost {
@@ -14,7 +15,7 @@ pub struct NacosHost {
     #[serde(rename = "clusterName")]
     pub cluster_name: String,
     pub service: Option<String>,
-    pub metadata: HashMap<String, String>,
+    pub metadata: InstanceMetadata,
     #[serde(rename = "instanceHeartBeatInterval")]
     pub instance_heart_beat_interval: Option<i32>,
     #[serde(rename = "instanceHeartBeatTimeOut")]
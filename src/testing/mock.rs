@@ -0,0 +1,771 @@
+use crate::NacosConfig;
+use crate::model::config_vo::{ConfigInfo, NacosPage};
+use crate::model::err::{NacosError, NacosErrorKind};
+use crate::model::metadata::InstanceMetadata;
+use crate::model::service_vo::{NacosBeat, NacosClusterInfo, NacosHost, NacosServerView, NacosServiceInfo, NacosServiceList};
+use crate::util::net::wildcard_match;
+use hyper::{Body, HeaderMap, Method, Request, Response, Server, StatusCode};
+use hyper::header::{CONTENT_TYPE, HeaderValue};
+use hyper::service::{make_service_fn, service_fn};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::{self, Instant};
+
+const PUBLIC_NAMESPACE: &str = "public";
+const DEFAULT_GROUP: &str = "DEFAULT_GROUP";
+const DEFAULT_CLUSTER: &str = "DEFAULT";
+/// 默认的心跳间隔 毫秒
+const BEAT_INTERVAL: u64 = 5000;
+const BEAT_OK: i32 = 10200;
+const RESOURCE_NOT_FOUND: i32 = 20404;
+const DEFAULT_LONG_POLLING_TIMEOUT: u64 = 30000;
+
+/// 进程内的 nacos 模拟服务端 , 监听本机的随机端口 , 以内存保存实例 服务与配置 ;
+/// 实现 v1 的实例 服务 心跳 配置与配置监听接口 , 不支持导入导出 克隆 历史与灰度发布 ; 丢弃时停止服务
+/// the in-process stand-in of nacos listening on a random local port , keeping instances , services and configs
+/// in memory . it implements the v1 instance , service , heartbeat , config and listener apis ,
+/// without import , export , clone , history and beta publishing . it stops when dropped .
+/// ```rust,no_run
+/// use nacos_api::{NacosClient, NacosConfigClient, ServerConfig};
+/// use nacos_api::testing::MockNacos;
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let nacos = MockNacos::start().await?;
+/// let client = NacosClient::new(&nacos.nacos_config(), ServerConfig::new("127.0.0.1", 8080, "orders", None, None));
+/// let registration = client.try_register(None).await?;
+/// assert_eq!(1, nacos.instances("orders").len());
+///
+/// nacos.set_config("orders.yaml", "DEFAULT_GROUP", None, "max_items: 10", Some("yaml"));
+/// let content = NacosConfigClient::new("orders.yaml", "DEFAULT_GROUP", None)
+///     .config_api()
+///     .get_configs(&nacos.nacos_config())
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct MockNacos {
+    addr: SocketAddr,
+    state: Arc<MockState>,
+    server: JoinHandle<()>,
+}
+
+impl MockNacos {
+    /// 在本机的随机端口上启动
+    /// start on a random local port .
+    pub async fn start() -> Result<Self, NacosError> {
+        let state = Arc::new(MockState::new());
+        let service_state = state.clone();
+        let make_service = make_service_fn(move |_| {
+            let state = service_state.clone();
+            async move { Ok::<_, Infallible>(service_fn(move |req| handle(state.clone(), req))) }
+        });
+        let server = Server::try_bind(&SocketAddr::from(([127, 0, 0, 1], 0)))
+            .map_err(|e| NacosError::new(NacosErrorKind::Server, &format!("mock nacos bind error: {}", e)))?
+            .serve(make_service);
+        let addr = server.local_addr();
+        let server = tokio::spawn(async move {
+            if let Err(e) = server.await {
+                println!(" -- [warn] mock nacos err : {}", e);
+            }
+        });
+        Ok(Self { addr, state, server })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// 指向当前模拟服务端的地址
+    pub fn nacos_config(&self) -> NacosConfig {
+        NacosConfig::new("http", &self.addr.ip().to_string(), self.addr.port() as u32)
+    }
+}
+
+impl MockNacos {
+    /// public 命名空间中服务的所有实例 , [service_name] 可以为 `group@@name` 形式 , 缺省分组为 DEFAULT_GROUP
+    pub fn instances(&self, service_name: &str) -> Vec<NacosHost> {
+        let key = ServiceKey::parse(None, None, service_name);
+        self.state.data().services.get(&key)
+            .map(|service| service.instances.iter().map(|i| i.host(&key)).collect())
+            .unwrap_or_default()
+    }
+
+    /// 实例收到的心跳次数 , 实例不存在时为 0
+    pub fn beat_count(&self, service_name: &str, ip: &str, port: u16) -> u64 {
+        let key = ServiceKey::parse(None, None, service_name);
+        self.state.data().services.get(&key)
+            .and_then(|service| service.instances.iter().find(|i| i.ip == ip && i.port == port as i32))
+            .map(|i| i.beats)
+            .unwrap_or_default()
+    }
+
+    /// 心跳响应中的心跳间隔 毫秒 , 默认为 5000 ; 测试中调小以加快心跳
+    /// the beat interval millis in the beat response , 5000 by default . turn it down to speed up the beats in tests .
+    pub fn set_beat_interval(&self, millis: u64) {
+        self.state.beat_interval.store(millis, Ordering::Relaxed);
+    }
+
+    /// 清空所有实例 , 模拟服务端重启后不再认识已注册的实例
+    /// clear all instances , simulating a restarted server which no longer knows the registered instances .
+    pub fn clear_instances(&self) {
+        for service in self.state.data().services.values_mut() {
+            service.instances.clear();
+        }
+    }
+
+    /// 当前保存的配置内容
+    pub fn config(&self, data_id: &str, group: &str, tenant: Option<&str>) -> Option<String> {
+        let key = ConfigKey::new(data_id, group, tenant);
+        self.state.data().configs.get(&key).map(|c| c.content.clone())
+    }
+
+    /// 写入配置并通知监听者 , 等同于从控制台发布
+    /// write the config and notify the listeners , the same as publishing from the console .
+    pub fn set_config(&self, data_id: &str, group: &str, tenant: Option<&str>, content: &str, config_type: Option<&str>) {
+        let key = ConfigKey::new(data_id, group, tenant);
+        self.state.data().publish(key, content, config_type.map(|t| t.to_string()), None, None);
+        self.state.notify_config_changed();
+    }
+
+    /// 删除配置并通知监听者
+    pub fn remove_config(&self, data_id: &str, group: &str, tenant: Option<&str>) {
+        let key = ConfigKey::new(data_id, group, tenant);
+        self.state.data().configs.remove(&key);
+        self.state.notify_config_changed();
+    }
+}
+
+impl Drop for MockNacos {
+    fn drop(&mut self) {
+        self.server.abort()
+    }
+}
+
+struct MockState {
+    data: Mutex<Data>,
+    /// 每次配置变更时加一 , 唤醒长轮询
+    config_version: watch::Sender<u64>,
+    beat_interval: AtomicU64,
+}
+
+impl MockState {
+    fn new() -> Self {
+        Self {
+            data: Mutex::new(Data::default()),
+            config_version: watch::channel(0).0,
+            beat_interval: AtomicU64::new(BEAT_INTERVAL),
+        }
+    }
+
+    fn data(&self) -> MutexGuard<'_, Data> {
+        self.data.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn notify_config_changed(&self) {
+        self.config_version.send_modify(|v| *v += 1);
+    }
+}
+
+#[derive(Default)]
+struct Data {
+    services: HashMap<ServiceKey, Service>,
+    configs: HashMap<ConfigKey, StoredConfig>,
+    next_config_id: i64,
+}
+
+impl Data {
+    fn publish(&mut self, key: ConfigKey, content: &str, config_type: Option<String>, app_name: Option<String>,
+               encrypted_data_key: Option<String>) {
+        let id = match self.configs.get(&key) {
+            Some(config) => config.id,
+            None => {
+                self.next_config_id += 1;
+                self.next_config_id
+            }
+        };
+        self.configs.insert(key, StoredConfig {
+            id,
+            content: content.to_string(),
+            md5: md5_of(content),
+            config_type,
+            app_name,
+            encrypted_data_key,
+        });
+    }
+}
+
+/// 命名空间 分组 服务名
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ServiceKey {
+    namespace: String,
+    group: String,
+    name: String,
+}
+
+impl ServiceKey {
+    /// [service_name] 为 `group@@name` 时以其中的分组为准
+    fn parse(namespace: Option<&str>, group: Option<&str>, service_name: &str) -> Self {
+        let (group, name) = match service_name.split_once("@@") {
+            Some((group, name)) => (group, name),
+            None => (group.unwrap_or(DEFAULT_GROUP), service_name),
+        };
+        Self {
+            namespace: namespace.unwrap_or(PUBLIC_NAMESPACE).to_string(),
+            group: group.to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    fn from_params(params: &Params) -> Result<Self, Rejection> {
+        Ok(Self::parse(params.get("namespaceId"), params.get("groupName"), params.required("serviceName")?))
+    }
+
+    fn grouped_name(&self) -> String {
+        format!("{}@@{}", self.group, self.name)
+    }
+}
+
+#[derive(Default)]
+struct Service {
+    protect_threshold: f64,
+    metadata: HashMap<String, String>,
+    instances: Vec<Instance>,
+}
+
+struct Instance {
+    ip: String,
+    port: i32,
+    cluster: String,
+    weight: f64,
+    healthy: bool,
+    enabled: bool,
+    ephemeral: bool,
+    metadata: InstanceMetadata,
+    beats: u64,
+}
+
+impl Instance {
+    fn host(&self, key: &ServiceKey) -> NacosHost {
+        NacosHost {
+            instance_id: format!("{}#{}#{}#{}", self.ip, self.port, self.cluster, key.grouped_name()),
+            ip: self.ip.clone(),
+            port: self.port,
+            weight: self.weight,
+            healthy: self.healthy,
+            enabled: Some(self.enabled),
+            ephemeral: Some(self.ephemeral),
+            cluster_name: self.cluster.clone(),
+            service: Some(key.grouped_name()),
+            metadata: self.metadata.clone(),
+            instance_heart_beat_interval: Some(BEAT_INTERVAL as i32),
+            instance_heart_beat_time_out: Some(3 * BEAT_INTERVAL as i32),
+            ip_delete_timeout: Some(6 * BEAT_INTERVAL as i32),
+            instance_id_generator: Some("simple".to_string()),
+        }
+    }
+
+    /// [cluster] 为 None 时不限集群
+    fn matches(&self, ip: &str, port: i32, cluster: Option<&str>) -> bool {
+        self.ip == ip && self.port == port && cluster.is_none_or(|c| self.cluster == c)
+    }
+}
+
+/// 命名空间 ( public 为空 ) 分组 dataId
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ConfigKey {
+    tenant: String,
+    group: String,
+    data_id: String,
+}
+
+impl ConfigKey {
+    fn new(data_id: &str, group: &str, tenant: Option<&str>) -> Self {
+        let group = if group.is_empty() { DEFAULT_GROUP } else { group };
+        Self { tenant: tenant.unwrap_or_default().to_string(), group: group.to_string(), data_id: data_id.to_string() }
+    }
+
+    fn from_params(params: &Params) -> Result<Self, Rejection> {
+        Ok(Self::new(params.required("dataId")?, params.get("group").unwrap_or(DEFAULT_GROUP), params.get("tenant")))
+    }
+}
+
+struct StoredConfig {
+    id: i64,
+    content: String,
+    md5: String,
+    config_type: Option<String>,
+    app_name: Option<String>,
+    encrypted_data_key: Option<String>,
+}
+
+/// 查询串与表单中的参数 , 空值视为缺失
+struct Params(HashMap<String, String>);
+
+impl Params {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(|v| v.as_str()).filter(|v| !v.is_empty())
+    }
+
+    fn has(&self, key: &str) -> bool {
+        self.0.contains_key(key)
+    }
+
+    fn required(&self, key: &str) -> Result<&str, Rejection> {
+        self.get(key).ok_or_else(|| Rejection::bad_request(&format!("Required parameter '{}' is not present", key)))
+    }
+
+    fn parse<T: FromStr>(&self, key: &str) -> Result<Option<T>, Rejection> {
+        self.get(key)
+            .map(|v| v.parse().map_err(|_| Rejection::bad_request(&format!("bad parameter {} : {}", key, v))))
+            .transpose()
+    }
+}
+
+/// 以错误码与原因拒绝请求
+struct Rejection {
+    status: StatusCode,
+    reason: String,
+}
+
+impl Rejection {
+    fn new(status: StatusCode, reason: &str) -> Self {
+        Self { status, reason: reason.to_string() }
+    }
+
+    fn bad_request(reason: &str) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, reason)
+    }
+
+    fn unsupported(api: &str) -> Self {
+        Self::new(StatusCode::NOT_IMPLEMENTED, &format!("{} is not supported by the mock nacos", api))
+    }
+}
+
+type Reply = Result<Response<Body>, Rejection>;
+
+async fn handle(state: Arc<MockState>, req: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let method = req.method().clone();
+    let path = req.uri().path().trim_start_matches("/nacos").to_string();
+    let headers = req.headers().clone();
+    let mut params: HashMap<String, String> = parse_form(req.uri().query().unwrap_or_default().as_bytes());
+    let is_form = headers.get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/x-www-form-urlencoded"));
+    if is_form {
+        let body = hyper::body::to_bytes(req.into_body()).await.unwrap_or_default();
+        params.extend(parse_form(&body));
+    }
+    let params = Params(params);
+    let reply = match (&method, path.as_str()) {
+        (&Method::POST, "/v1/ns/instance") => register_instance(&state, &params),
+        (&Method::DELETE, "/v1/ns/instance") => remove_instance(&state, &params),
+        (&Method::PUT, "/v1/ns/instance") => update_instance(&state, &params),
+        (&Method::GET, "/v1/ns/instance") => get_instance(&state, &params),
+        (&Method::GET, "/v1/ns/instance/list") => list_instances(&state, &params),
+        (&Method::PUT, "/v1/ns/instance/beat") => beat(&state, &params),
+        (&Method::PUT, "/v1/ns/health/instance") => update_health(&state, &params),
+        (&Method::POST, "/v1/ns/service") => create_service(&state, &params),
+        (&Method::DELETE, "/v1/ns/service") => delete_service(&state, &params),
+        (&Method::PUT, "/v1/ns/service") => update_service(&state, &params),
+        (&Method::GET, "/v1/ns/service") => get_service(&state, &params),
+        (&Method::GET, "/v1/ns/service/list") => list_services(&state, &params),
+        (&Method::GET, "/v1/cs/configs") => get_config(&state, &params),
+        (&Method::POST, "/v1/cs/configs") => publish_config(&state, &params, &headers),
+        (&Method::DELETE, "/v1/cs/configs") => delete_config(&state, &params),
+        (&Method::POST, "/v1/cs/configs/listener") => listen_configs(&state, &params, &headers).await,
+        (_, "/v1/cs/history") | (_, "/v1/cs/history/previous") => Err(Rejection::unsupported("config history")),
+        _ => Err(Rejection::new(StatusCode::NOT_FOUND, &format!("no api {} {} in the mock nacos", method, path))),
+    };
+    Ok(reply.unwrap_or_else(|rejection| text(rejection.status, &rejection.reason)))
+}
+
+fn register_instance(state: &MockState, params: &Params) -> Reply {
+    let key = ServiceKey::from_params(params)?;
+    let ip = params.required("ip")?.to_string();
+    let port = params.parse::<i32>("port")?.ok_or_else(|| Rejection::bad_request("Required parameter 'port' is not present"))?;
+    let cluster = params.get("clusterName").unwrap_or(DEFAULT_CLUSTER).to_string();
+    let instance = Instance {
+        weight: params.parse("weight")?.unwrap_or(1.0),
+        healthy: params.parse("healthy")?.unwrap_or(true),
+        enabled: params.parse("enabled")?.unwrap_or(true),
+        ephemeral: params.parse("ephemeral")?.unwrap_or(true),
+        metadata: parse_instance_metadata(params)?.unwrap_or_default(),
+        beats: 0,
+        ip,
+        port,
+        cluster,
+    };
+    let mut data = state.data();
+    let service = data.services.entry(key).or_default();
+    service.instances.retain(|i| !i.matches(&instance.ip, instance.port, Some(&instance.cluster)));
+    service.instances.push(instance);
+    Ok(text(StatusCode::OK, "ok"))
+}
+
+fn remove_instance(state: &MockState, params: &Params) -> Reply {
+    let key = ServiceKey::from_params(params)?;
+    let (ip, port) = instance_addr(params)?;
+    let cluster = params.get("clusterName");
+    if let Some(service) = state.data().services.get_mut(&key) {
+        service.instances.retain(|i| !i.matches(ip, port, cluster));
+    }
+    Ok(text(StatusCode::OK, "ok"))
+}
+
+fn update_instance(state: &MockState, params: &Params) -> Reply {
+    let key = ServiceKey::from_params(params)?;
+    let (ip, port) = instance_addr(params)?;
+    let metadata = parse_instance_metadata(params)?;
+    let mut data = state.data();
+    let instance = find_instance(&mut data, &key, ip, port, params.get("clusterName"))?;
+    if let Some(weight) = params.parse("weight")? { instance.weight = weight; }
+    if let Some(enabled) = params.parse("enabled")? { instance.enabled = enabled; }
+    if let Some(healthy) = params.parse("healthy")? { instance.healthy = healthy; }
+    if let Some(metadata) = metadata { instance.metadata = metadata; }
+    Ok(text(StatusCode::OK, "ok"))
+}
+
+fn update_health(state: &MockState, params: &Params) -> Reply {
+    let key = ServiceKey::from_params(params)?;
+    let (ip, port) = instance_addr(params)?;
+    let healthy = params.parse("healthy")?.ok_or_else(|| Rejection::bad_request("Required parameter 'healthy' is not present"))?;
+    let mut data = state.data();
+    let instance = find_instance(&mut data, &key, ip, port, params.get("clusterName"))?;
+    // 与 nacos 一致 , 临时实例的健康状态只由心跳决定
+    if instance.ephemeral {
+        return Err(Rejection::bad_request(&format!("health of ephemeral instance {}:{} is decided by hart beat", ip, port)));
+    }
+    instance.healthy = healthy;
+    Ok(text(StatusCode::OK, "ok"))
+}
+
+fn get_instance(state: &MockState, params: &Params) -> Reply {
+    let key = ServiceKey::from_params(params)?;
+    let (ip, port) = instance_addr(params)?;
+    let cluster = params.get("cluster").or_else(|| params.get("clusterName"));
+    let mut data = state.data();
+    let host = find_instance(&mut data, &key, ip, port, cluster)?.host(&key);
+    Ok(json(&host))
+}
+
+fn list_instances(state: &MockState, params: &Params) -> Reply {
+    let key = ServiceKey::from_params(params)?;
+    let clusters: Vec<&str> = params.get("clusters").map(|c| c.split(',').collect()).unwrap_or_default();
+    let healthy_only = params.parse("healthyOnly")?.unwrap_or(false);
+    let data = state.data();
+    let hosts = data.services.get(&key)
+        .map(|service| service.instances.iter()
+            .filter(|i| clusters.is_empty() || clusters.contains(&i.cluster.as_str()))
+            .filter(|i| !healthy_only || (i.healthy && i.enabled))
+            .map(|i| i.host(&key))
+            .collect())
+        .unwrap_or_default();
+    Ok(json(&NacosServerView {
+        name: Some(key.grouped_name()),
+        group_name: Some(key.group.clone()),
+        clusters: Some(clusters.join(",")),
+        cache_millis: Some(10000),
+        hosts: Some(hosts),
+        last_ref_time: Some(now_millis()),
+        checksum: Some(String::new()),
+        all_ips: Some(false),
+        reach_protection_threshold: Some(false),
+        valid: Some(true),
+    }))
+}
+
+fn beat(state: &MockState, params: &Params) -> Reply {
+    let key = ServiceKey::from_params(params)?;
+    let (ip, port) = instance_addr(params)?;
+    let mut data = state.data();
+    let code = match find_instance(&mut data, &key, ip, port, None) {
+        Ok(instance) => {
+            instance.beats += 1;
+            instance.healthy = true;
+            BEAT_OK
+        }
+        Err(_) => RESOURCE_NOT_FOUND,
+    };
+    let client_beat_interval = state.beat_interval.load(Ordering::Relaxed);
+    Ok(json(&NacosBeat { client_beat_interval, code, light_beat_enabled: true }))
+}
+
+fn create_service(state: &MockState, params: &Params) -> Reply {
+    let key = ServiceKey::from_params(params)?;
+    let service = Service {
+        protect_threshold: params.parse("protectThreshold")?.unwrap_or_default(),
+        metadata: params.get("metadata").map(parse_service_metadata).unwrap_or_default(),
+        instances: Vec::new(),
+    };
+    let mut data = state.data();
+    if data.services.contains_key(&key) {
+        return Err(Rejection::bad_request(&format!("specified service already exists, serviceName : {}", key.grouped_name())));
+    }
+    data.services.insert(key, service);
+    Ok(text(StatusCode::OK, "ok"))
+}
+
+fn delete_service(state: &MockState, params: &Params) -> Reply {
+    let key = ServiceKey::from_params(params)?;
+    let mut data = state.data();
+    match data.services.get(&key) {
+        None => return Err(service_not_found(&key)),
+        Some(service) if !service.instances.is_empty() =>
+            return Err(Rejection::bad_request(&format!("service {} is not empty, can't be delete", key.grouped_name()))),
+        Some(_) => data.services.remove(&key),
+    };
+    Ok(text(StatusCode::OK, "ok"))
+}
+
+fn update_service(state: &MockState, params: &Params) -> Reply {
+    let key = ServiceKey::from_params(params)?;
+    let protect_threshold = params.parse("protectThreshold")?;
+    let mut data = state.data();
+    let service = data.services.get_mut(&key).ok_or_else(|| service_not_found(&key))?;
+    if let Some(protect_threshold) = protect_threshold { service.protect_threshold = protect_threshold; }
+    if let Some(metadata) = params.get("metadata") { service.metadata = parse_service_metadata(metadata); }
+    Ok(text(StatusCode::OK, "ok"))
+}
+
+fn get_service(state: &MockState, params: &Params) -> Reply {
+    let key = ServiceKey::from_params(params)?;
+    let data = state.data();
+    let service = data.services.get(&key).ok_or_else(|| service_not_found(&key))?;
+    let clusters: BTreeSet<&str> = service.instances.iter().map(|i| i.cluster.as_str()).collect();
+    let mut selector = HashMap::new();
+    selector.insert("type".to_string(), "none".to_string());
+    Ok(json(&NacosServiceInfo {
+        namespace_id: key.namespace.clone(),
+        group_name: key.group.clone(),
+        name: key.name.clone(),
+        protect_threshold: service.protect_threshold,
+        metadata: service.metadata.clone(),
+        selector,
+        clusters: clusters.into_iter().map(|name| {
+            let mut health_checker = HashMap::new();
+            health_checker.insert("type".to_string(), "NONE".to_string());
+            NacosClusterInfo { health_checker, metadata: HashMap::new(), name: name.to_string() }
+        }).collect(),
+    }))
+}
+
+fn list_services(state: &MockState, params: &Params) -> Reply {
+    let page_no = params.parse::<usize>("pageNo")?.unwrap_or(1).max(1);
+    let page_size = params.parse::<usize>("pageSize")?.unwrap_or(10);
+    let namespace = params.get("namespaceId").unwrap_or(PUBLIC_NAMESPACE);
+    let group = params.get("groupName").unwrap_or(DEFAULT_GROUP);
+    let data = state.data();
+    let names: BTreeSet<&str> = data.services.keys()
+        .filter(|k| k.namespace == namespace && k.group == group)
+        .map(|k| k.name.as_str())
+        .collect();
+    Ok(json(&NacosServiceList {
+        count: names.len() as isize,
+        doms: names.into_iter().skip((page_no - 1) * page_size).take(page_size).map(|n| n.to_string()).collect(),
+    }))
+}
+
+fn get_config(state: &MockState, params: &Params) -> Reply {
+    if let Some(mode) = params.get("search") { return search_configs(state, params, mode); }
+    if params.has("export") || params.has("exportV2") { return Err(Rejection::unsupported("config export")); }
+    let key = ConfigKey::from_params(params)?;
+    let data = state.data();
+    let config = data.configs.get(&key).ok_or_else(|| Rejection::new(StatusCode::NOT_FOUND, "config data not exist"))?;
+    let mut resp = text(StatusCode::OK, &config.content);
+    let headers = resp.headers_mut();
+    insert_header(headers, "Config-Type", config.config_type.as_deref().unwrap_or("text"));
+    insert_header(headers, "Content-MD5", &config.md5);
+    if let Some(key) = &config.encrypted_data_key { insert_header(headers, "Encrypted-Data-Key", key); }
+    Ok(resp)
+}
+
+fn publish_config(state: &MockState, params: &Params, headers: &HeaderMap) -> Reply {
+    if params.has("import") { return Err(Rejection::unsupported("config import")); }
+    if params.has("clone") { return Err(Rejection::unsupported("config clone")); }
+    if headers.contains_key("betaIps") { return Err(Rejection::unsupported("beta publishing")); }
+    let key = ConfigKey::from_params(params)?;
+    let content = params.required("content")?;
+    let mut data = state.data();
+    if let Some(cas_md5) = params.get("casMd5") {
+        let current = data.configs.get(&key).map_or_else(|| md5_of(""), |c| c.md5.clone());
+        if current != cas_md5 {
+            return Err(Rejection::new(StatusCode::INTERNAL_SERVER_ERROR, "cas publish fail, server md5 may have changed."));
+        }
+    }
+    data.publish(key, content,
+                 params.get("type").map(|t| t.to_string()),
+                 params.get("appName").map(|a| a.to_string()),
+                 params.get("encryptedDataKey").map(|k| k.to_string()));
+    drop(data);
+    state.notify_config_changed();
+    Ok(text(StatusCode::OK, "true"))
+}
+
+fn delete_config(state: &MockState, params: &Params) -> Reply {
+    let key = ConfigKey::from_params(params)?;
+    state.data().configs.remove(&key);
+    state.notify_config_changed();
+    Ok(text(StatusCode::OK, "true"))
+}
+
+/// 精确搜索时 dataId 与 group 为空表示不限 ; 模糊搜索时支持 `*` 通配 , 不含 `*` 时按包含匹配
+fn search_configs(state: &MockState, params: &Params, mode: &str) -> Reply {
+    let blur = match mode {
+        "accurate" => false,
+        "blur" => true,
+        other => return Err(Rejection::bad_request(&format!("bad search mode {}", other))),
+    };
+    let page_no = params.parse::<usize>("pageNo")?.unwrap_or(1).max(1);
+    let page_size = params.parse::<usize>("pageSize")?.unwrap_or(10).max(1);
+    let tenant = params.get("tenant").unwrap_or_default();
+    let matches = |pattern: Option<&str>, value: &str| match pattern {
+        None => true,
+        Some(pattern) if !blur => pattern == value,
+        Some(pattern) if pattern.contains('*') => wildcard_match(pattern, value),
+        Some(pattern) => value.contains(pattern),
+    };
+    let data = state.data();
+    let mut found: Vec<(&ConfigKey, &StoredConfig)> = data.configs.iter()
+        .filter(|(k, c)| k.tenant == tenant
+            && matches(params.get("dataId"), &k.data_id)
+            && matches(params.get("group"), &k.group)
+            && params.get("appName").is_none_or(|app| c.app_name.as_deref() == Some(app)))
+        .collect();
+    found.sort_by_key(|(_, c)| c.id);
+    let total = found.len();
+    Ok(json(&NacosPage {
+        total_count: total as i64,
+        page_number: page_no as i64,
+        pages_available: total.div_ceil(page_size) as i64,
+        page_items: found.into_iter().skip((page_no - 1) * page_size).take(page_size).map(|(k, c)| ConfigInfo {
+            id: c.id,
+            data_id: k.data_id.clone(),
+            group: k.group.clone(),
+            content: if blur { None } else { Some(c.content.clone()) },
+            md5: Some(c.md5.clone()),
+            config_type: c.config_type.clone(),
+            tenant: Some(k.tenant.clone()),
+            app_name: c.app_name.clone(),
+        }).collect(),
+    }))
+}
+
+/// 长轮询 : 任一配置的 md5 与客户端不一致时立即返回变更的配置 , 否则等待变更直到超时返回空
+/// long polling : return the changed configs immediately if any md5 differs from the client ,
+/// or else wait for changes until timeout and return empty .
+async fn listen_configs(state: &MockState, params: &Params, headers: &HeaderMap) -> Reply {
+    let listening = params.required("Listening-Configs")?;
+    let entries: Vec<(ConfigKey, &str)> = listening.split('\u{1}')
+        .filter(|e| !e.is_empty())
+        .map(|e| {
+            let fields: Vec<&str> = e.split('\u{2}').collect();
+            match fields.as_slice() {
+                [data_id, group, md5] => Ok((ConfigKey::new(data_id, group, None), *md5)),
+                [data_id, group, md5, tenant] => Ok((ConfigKey::new(data_id, group, Some(tenant)), *md5)),
+                _ => Err(Rejection::bad_request(&format!("bad listening config {:?}", e))),
+            }
+        })
+        .collect::<Result<_, _>>()?;
+    let timeout = headers.get("Long-Pulling-Timeout")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_LONG_POLLING_TIMEOUT);
+    let deadline = Instant::now() + Duration::from_millis(timeout);
+    // 先订阅再比较 , 比较之后的变更不会丢失
+    let mut version = state.config_version.subscribe();
+    loop {
+        let changed: String = {
+            let data = state.data();
+            entries.iter()
+                .filter(|(key, md5)| data.configs.get(key).map_or("", |c| c.md5.as_str()) != *md5)
+                .map(|(key, _)| match key.tenant.is_empty() {
+                    true => format!("{}\u{2}{}\u{1}", key.data_id, key.group),
+                    false => format!("{}\u{2}{}\u{2}{}\u{1}", key.data_id, key.group, key.tenant),
+                })
+                .collect()
+        };
+        if !changed.is_empty() {
+            return Ok(text(StatusCode::OK, &form_urlencoded::byte_serialize(changed.as_bytes()).collect::<String>()));
+        }
+        match time::timeout_at(deadline, version.changed()).await {
+            Ok(Ok(_)) => continue,
+            _ => return Ok(text(StatusCode::OK, "")),
+        }
+    }
+}
+
+fn find_instance<'a>(data: &'a mut Data, key: &ServiceKey, ip: &str, port: i32, cluster: Option<&str>)
+                     -> Result<&'a mut Instance, Rejection> {
+    data.services.get_mut(key)
+        .and_then(|service| service.instances.iter_mut().find(|i| i.matches(ip, port, cluster)))
+        .ok_or_else(|| Rejection::new(StatusCode::NOT_FOUND,
+                                      &format!("no matched ip {}:{} found in service {}", ip, port, key.grouped_name())))
+}
+
+fn instance_addr(params: &Params) -> Result<(&str, i32), Rejection> {
+    let ip = params.required("ip")?;
+    let port = params.parse("port")?.ok_or_else(|| Rejection::bad_request("Required parameter 'port' is not present"))?;
+    Ok((ip, port))
+}
+
+fn service_not_found(key: &ServiceKey) -> Rejection {
+    Rejection::new(StatusCode::NOT_FOUND, &format!("service {} is not found", key.grouped_name()))
+}
+
+/// 实例的扩展信息为 JSON 对象
+fn parse_instance_metadata(params: &Params) -> Result<Option<InstanceMetadata>, Rejection> {
+    params.get("metadata")
+        .map(|m| serde_json::from_str(m).map_err(|e| Rejection::bad_request(&format!("bad metadata {} : {}", m, e))))
+        .transpose()
+}
+
+/// 服务的扩展信息为 JSON 对象或 `k1=v1,k2=v2`
+fn parse_service_metadata(metadata: &str) -> HashMap<String, String> {
+    serde_json::from_str(metadata).unwrap_or_else(|_| metadata.split(',')
+        .filter_map(|kv| kv.split_once('='))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect())
+}
+
+fn parse_form(input: &[u8]) -> HashMap<String, String> {
+    form_urlencoded::parse(input).into_owned().collect()
+}
+
+fn md5_of(content: &str) -> String {
+    format!("{:x}", md5::compute(content))
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as i64).unwrap_or_default()
+}
+
+fn insert_header(headers: &mut HeaderMap, name: &'static str, value: &str) {
+    if let Ok(value) = HeaderValue::from_str(value) {
+        headers.insert(name, value);
+    }
+}
+
+fn text(status: StatusCode, body: &str) -> Response<Body> {
+    let mut resp = Response::new(Body::from(body.to_string()));
+    *resp.status_mut() = status;
+    resp
+}
+
+fn json<T: Serialize>(value: &T) -> Response<Body> {
+    match serde_json::to_string(value) {
+        Ok(body) => {
+            let mut resp = text(StatusCode::OK, &body);
+            resp.headers_mut().insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+            resp
+        }
+        Err(e) => text(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
@@ -0,0 +1,5 @@
+pub mod mock;
+pub mod transport;
+
+pub use mock::MockNacos;
+pub use transport::{Exchange, RecordingTransport, ReplayTransport};
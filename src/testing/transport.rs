@@ -0,0 +1,159 @@
+use crate::model::err::{NacosError, NacosErrorKind};
+use crate::util::transport::{HttpRequest, HttpResponse, HttpTransport, TransportFuture};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// 一次请求与其响应
+/// a request with its response .
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Exchange {
+    pub request: HttpRequest,
+    pub response: HttpResponse,
+}
+
+impl Exchange {
+    /// 方法 url 查询参数与请求体相同时匹配 , 忽略请求头与超时
+    fn matches(&self, request: &HttpRequest) -> bool {
+        let recorded = &self.request;
+        recorded.method == request.method
+            && recorded.url == request.url
+            && recorded.query == request.query
+            && recorded.body == request.body
+    }
+}
+
+/// 记录经过 [inner] 的请求与响应 , 可以保存为 json 供 [ReplayTransport] 回放
+/// record the requests through [inner] with their responses , which can be saved as json and replayed by
+/// [ReplayTransport] .
+/// ```rust,no_run
+/// use nacos_api::{NacosConfigApi, DeployConfig};
+/// use nacos_api::testing::{MockNacos, RecordingTransport};
+/// use std::sync::Arc;
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let nacos = MockNacos::start().await?;
+/// let recording = Arc::new(RecordingTransport::new(nacos.nacos_config().transport().clone()));
+/// let nacos_config = nacos.nacos_config().with_transport(recording.clone());
+/// NacosConfigApi::new(DeployConfig::new("orders.yaml", "DEFAULT_GROUP", None)).get_configs(&nacos_config).await?;
+/// recording.save("tests/fixtures/orders.json")?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct RecordingTransport {
+    inner: Arc<dyn HttpTransport>,
+    exchanges: Mutex<Vec<Exchange>>,
+}
+
+impl RecordingTransport {
+    pub fn new(inner: Arc<dyn HttpTransport>) -> Self {
+        Self { inner, exchanges: Mutex::new(Vec::new()) }
+    }
+
+    /// 按发送顺序返回已记录的请求与响应 , 未能送达的请求不记录
+    pub fn exchanges(&self) -> Vec<Exchange> {
+        self.exchanges.lock().unwrap().clone()
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn Error>> {
+        let json = serde_json::to_string_pretty(&self.exchanges())?;
+        fs::write(path, json)?;
+        Ok(())
+    }
+}
+
+impl HttpTransport for RecordingTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let response = self.inner.send(request.clone()).await?;
+            self.exchanges.lock().unwrap().push(Exchange { request, response: response.clone() });
+            Ok(response)
+        })
+    }
+}
+
+/// 按记录的请求与响应回放 , 不发送任何请求 ; 选择第一个未使用的匹配项 , 匹配项用尽后重复最后一个 ,
+/// 没有匹配项时返回 NotFound 错误
+/// replay the recorded requests with their responses without sending anything . the first unused matching
+/// exchange is chosen , the last one is repeated once they are used up , and a NotFound err is returned
+/// if nothing matches .
+/// ```rust,no_run
+/// use nacos_api::{NacosClient, NacosConfig, ServerConfig};
+/// use nacos_api::testing::ReplayTransport;
+/// use nacos_api::util::transport::{HttpRequest, HttpResponse};
+/// use std::collections::HashMap;
+/// use std::sync::Arc;
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// // recorded by RecordingTransport
+/// let replay = ReplayTransport::load("tests/fixtures/orders.json")?;
+/// // or written by hand
+/// let nacos = NacosConfig::new("http", "127.0.0.1", 8848);
+/// let replay = replay.on(
+///     HttpRequest::get(&nacos.addr("/v1/ns/operator/metrics")),
+///     HttpResponse::new(200, r#"{"status":"UP"}"#),
+/// );
+/// let nacos = nacos.with_transport(Arc::new(replay));
+/// # Ok(())
+/// # }
+/// ```
+pub struct ReplayTransport {
+    exchanges: Vec<Exchange>,
+    state: Mutex<ReplayState>,
+}
+
+#[derive(Default)]
+struct ReplayState {
+    used: Vec<bool>,
+    requests: Vec<HttpRequest>,
+}
+
+impl ReplayTransport {
+    pub fn new(exchanges: Vec<Exchange>) -> Self {
+        let state = ReplayState { used: vec![false; exchanges.len()], requests: Vec::new() };
+        Self { exchanges, state: Mutex::new(state) }
+    }
+
+    /// 读取 [RecordingTransport::save] 保存的 json
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        let exchanges = serde_json::from_str::<Vec<Exchange>>(&fs::read_to_string(path)?)?;
+        Ok(Self::new(exchanges))
+    }
+
+    /// 加入一组请求与响应
+    /// add a request with its response .
+    pub fn on(mut self, request: HttpRequest, response: HttpResponse) -> Self {
+        self.exchanges.push(Exchange { request, response });
+        self.state.get_mut().unwrap().used.push(false);
+        self
+    }
+
+    /// 按接收顺序返回收到的请求 , 包括没有匹配项的请求
+    /// the received requests in order , including the unmatched ones .
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    fn reply(&self, request: HttpRequest) -> Result<HttpResponse, NacosError> {
+        let mut state = self.state.lock().unwrap();
+        let matched: Vec<_> = self.exchanges.iter().enumerate()
+            .filter(|(_, e)| e.matches(&request))
+            .map(|(i, _)| i)
+            .collect();
+        let chosen = matched.iter().copied().find(|i| !state.used[*i]).or_else(|| matched.last().copied());
+        let reason = format!("no recorded exchange for {:?} {}", request.method, request.url);
+        state.requests.push(request);
+        let i = chosen.ok_or_else(|| NacosError::new(NacosErrorKind::NotFound, &reason))?;
+        state.used[i] = true;
+        Ok(self.exchanges[i].response.clone())
+    }
+}
+
+impl HttpTransport for ReplayTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        let reply = self.reply(request);
+        Box::pin(async move { reply })
+    }
+}
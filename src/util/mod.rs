@@ -1,43 +1,45 @@
 use std::collections::HashMap;
-use reqwest::{Response, RequestBuilder, Client};
+use reqwest::Client;
 use std::error::Error;
-use crate::model::{self, Dto};
+use crate::model::{self, Dto, NacosConfig};
 use crate::model::err::NacosError;
 use lazy_static::lazy_static;
-use std::time::Duration;
+use transport::{HttpRequest, HttpResponse};
+
+pub mod net;
+pub mod properties;
+pub mod transport;
 
 lazy_static! {
     pub static ref CLIENT: Client = Client::new();
 }
 
-pub(crate) async fn query<F>(map: &HashMap<String, String>, func: F)
-                             -> Result<Response, Box<dyn Error>>
-    where F: Fn(&Client) -> RequestBuilder
-{
-    let resp = func(&CLIENT).query(map).timeout(Duration::from_secs(10)).send().await?;
+/// 以 [nacos] 的传输层发送携带查询参数 [map] 的请求
+pub(crate) async fn query(nacos: &NacosConfig, request: HttpRequest, map: &HashMap<String, String>)
+                          -> Result<HttpResponse, Box<dyn Error>> {
+    let resp = nacos.transport().send(request.query(map)).await?;
     Ok(resp)
 }
 
-pub(crate) async fn query_resp<T, F>(mut map: HashMap<String, String>, option: &Option<T>, func: F)
-                                     -> Result<Response, Box<dyn Error>>
-    where T: Dto,
-          F: Fn(&Client) -> RequestBuilder
+pub(crate) async fn query_resp<T>(nacos: &NacosConfig, request: HttpRequest, mut map: HashMap<String, String>, option: &Option<T>)
+                                  -> Result<HttpResponse, Box<dyn Error>>
+    where T: Dto
 {
     model::catch_mapping(&mut map, option);
-    let resp = query(&map, func).await?;
+    let resp = query(nacos, request, &map).await?;
     Ok(resp)
 }
 
-pub(crate) async fn query_and_ok<T, F>(map: HashMap<String, String>, option: &Option<T>, func: F)
-                                       -> Result<(), Box<dyn Error>>
-    where T: Dto,
-          F: Fn(&Client) -> RequestBuilder
+pub(crate) async fn query_and_ok<T>(nacos: &NacosConfig, request: HttpRequest, map: HashMap<String, String>, option: &Option<T>)
+                                    -> Result<(), Box<dyn Error>>
+    where T: Dto
 {
-    resp_assert(query_resp(map, option, func).await?, "ok").await
+    let resp = query_resp(nacos, request, map, option).await?;
+    resp_assert(resp, "ok")
 }
 
-pub(crate) async fn resp_assert(resp: Response, assert: &str) -> Result<(), Box<dyn Error>> {
-    let result = resp.text().await?;
+pub(crate) fn resp_assert(resp: HttpResponse, assert: &str) -> Result<(), Box<dyn Error>> {
+    let result = resp.text();
     if result.ne(assert) { return Err(Box::from(NacosError::throw(&result))); }
     Ok(())
-}
\ No newline at end of file
+}
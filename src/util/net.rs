@@ -0,0 +1,294 @@
+use crate::model::err::{NacosError, NacosErrorKind};
+use std::env;
+use std::net::{IpAddr, UdpSocket};
+
+/// 直接指定本机地址 , 优先于其他所有规则
+pub const NACOS_CLIENT_IP: &str = "NACOS_CLIENT_IP";
+/// 以逗号分隔的 CIDR 白名单 , 如 `10.0.0.0/8,192.168.0.0/16`
+pub const NACOS_CLIENT_CIDRS: &str = "NACOS_CLIENT_CIDRS";
+/// 以逗号分隔的网卡名模式 , 支持 `*` 通配 , 如 `eth*,en*`
+pub const NACOS_CLIENT_INTERFACES: &str = "NACOS_CLIENT_INTERFACES";
+
+/// 未指定路由探测目标或目标不可用时使用的公网地址
+const PUBLIC_TARGET: &str = "8.8.8.8:80";
+/// 未指定网卡名模式时忽略的虚拟网卡
+const VIRTUAL_INTERFACES: &[&str] = &["lo*", "docker*", "veth*", "br-*", "virbr*", "cni*", "flannel*", "vmnet*", "utun*"];
+
+/// 探测本机的非回环地址 : 以 UDP 连接 [target] ( 不发送数据 ) 取路由选择的本地地址 ,
+/// [target] 为回环地址或不可达时改用公网地址探测
+/// detect the local non-loopback ip : take the local address chosen by the route when connecting [target]
+/// with UDP ( nothing sent ) , and detect by a public address if [target] is loopback or unreachable .
+pub fn local_ip(target: &str) -> Option<IpAddr> {
+    [target, PUBLIC_TARGET].iter()
+        .filter_map(|target| route_ip(target))
+        .find(|ip| !ip.is_loopback() && !ip.is_unspecified())
+}
+
+fn route_ip(target: &str) -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect(target).ok()?;
+    socket.local_addr().ok().map(|addr| addr.ip())
+}
+
+/// 按环境变量 [NACOS_CLIENT_IP] [NACOS_CLIENT_CIDRS] [NACOS_CLIENT_INTERFACES] 选择本机地址 ,
+/// 没有任何规则时以到 [target] ( 通常为 nacos 服务端 `ip:port` ) 的路由探测 ; 环境变量有误或没有可用地址时返回错误
+/// select the local ip by the env vars [NACOS_CLIENT_IP] [NACOS_CLIENT_CIDRS] [NACOS_CLIENT_INTERFACES] ,
+/// and detect by the route to [target] ( usually the `ip:port` of nacos server ) if there are no rules .
+/// return the err if the env vars are invalid or no ip is available .
+pub fn detect_ip(target: &str) -> Result<IpAddr, NacosError> {
+    IpSelector::from_env()?
+        .route_to(target)
+        .select()
+        .ok_or_else(|| NacosError::new(NacosErrorKind::Validate,
+                                       &format!("can not detect local ip , set {}", NACOS_CLIENT_IP)))
+}
+
+/// 由 [NACOS_CLIENT_IP] 指定的本机地址 , 未设置时返回 None , 不是合法地址时返回错误
+pub fn client_ip() -> Result<Option<IpAddr>, NacosError> {
+    parse_client_ip(env::var(NACOS_CLIENT_IP).ok())
+}
+
+fn parse_client_ip(value: Option<String>) -> Result<Option<IpAddr>, NacosError> {
+    match value.as_deref().map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        Some(ip) => ip.parse().map(Some).map_err(|_| NacosError::new(NacosErrorKind::Validate,
+            &format!("invalid {} : {}", NACOS_CLIENT_IP, ip))),
+        None => Ok(None),
+    }
+}
+
+/// 本机地址的选择规则 ,
+/// the rules to select the local ip .
+/// ```rust,no_run
+/// use nacos_api::util::net::IpSelector;
+///
+/// # fn run() -> Result<(), nacos_api::model::err::NacosError> {
+/// let ip = IpSelector::new()
+///     .allow_cidr("10.0.0.0/8")?
+///     .interface("eth*")
+///     .select();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct IpSelector {
+    ip: Option<IpAddr>,
+    cidrs: Vec<Cidr>,
+    interfaces: Vec<String>,
+    route_target: Option<String>,
+}
+
+impl IpSelector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 由 [NACOS_CLIENT_IP] [NACOS_CLIENT_CIDRS] [NACOS_CLIENT_INTERFACES] 构造 , 环境变量有误时返回错误
+    pub fn from_env() -> Result<Self, NacosError> {
+        let mut selector = Self::new();
+        if let Some(ip) = client_ip()? {
+            selector = selector.ip(ip);
+        }
+        for cidr in list_var(NACOS_CLIENT_CIDRS) {
+            selector = selector.allow_cidr(&cidr)?;
+        }
+        for pattern in list_var(NACOS_CLIENT_INTERFACES) {
+            selector = selector.interface(&pattern);
+        }
+        Ok(selector)
+    }
+
+    /// 直接指定本机地址 , 优先于其他所有规则
+    pub fn ip(mut self, ip: IpAddr) -> Self {
+        self.ip = Some(ip);
+        self
+    }
+
+    /// 只选择在 [cidr] 中的地址 , 多个 CIDR 时靠前的优先
+    pub fn allow_cidr(mut self, cidr: &str) -> Result<Self, NacosError> {
+        self.cidrs.push(Cidr::parse(cidr)?);
+        Ok(self)
+    }
+
+    /// 只选择网卡名匹配 [pattern] 的地址 , 支持 `*` 通配 , 多个模式时靠前的优先
+    pub fn interface(mut self, pattern: &str) -> Self {
+        self.interfaces.push(pattern.to_string());
+        self
+    }
+
+    /// 路由探测的目标 `host:port` , 通常为 nacos 服务端 , 缺省时以公网地址探测
+    pub fn route_to(mut self, target: &str) -> Self {
+        self.route_target = Some(target.to_string());
+        self
+    }
+
+    /// 选择本机地址 : 直接指定的地址优先 , 其次为满足网卡名模式与 CIDR 白名单的非回环地址 , IPv4 优先 ;
+    /// 没有任何规则时忽略常见的虚拟网卡 , 都不满足时以路由探测
+    /// select the local ip : the specified ip first , then the non-loopback ip matching the interface patterns
+    /// and the CIDR allowlist , IPv4 preferred . the common virtual interfaces are ignored if there are no rules ,
+    /// and detect by the route if nothing matched .
+    pub fn select(&self) -> Option<IpAddr> {
+        if let Some(ip) = self.ip {
+            return Some(ip);
+        }
+        match self.candidates().into_iter().next() {
+            Some((_, ip)) => Some(ip),
+            None if self.cidrs.is_empty() && self.interfaces.is_empty() =>
+                local_ip(self.route_target.as_deref().unwrap_or(PUBLIC_TARGET)),
+            None => None,
+        }
+    }
+
+    /// 满足规则的网卡名与地址 , 按优先级排列
+    pub fn candidates(&self) -> Vec<(String, IpAddr)> {
+        let interfaces = match if_addrs::get_if_addrs() {
+            Ok(interfaces) => interfaces,
+            Err(e) => {
+                println!(" -- [warn] nacos list network interfaces err : {}", e);
+                return Vec::new();
+            }
+        };
+        let mut candidates: Vec<_> = interfaces.into_iter()
+            .filter(|i| !i.is_loopback() && !i.is_link_local())
+            .filter_map(|i| {
+                let ip = i.ip();
+                let interface_rank = self.interface_rank(&i.name)?;
+                let cidr_rank = self.cidr_rank(&ip)?;
+                Some(((ip.is_ipv6(), interface_rank, cidr_rank), i.name, ip))
+            })
+            .collect();
+        candidates.sort_by_key(|(rank, _, _)| *rank);
+        candidates.into_iter().map(|(_, name, ip)| (name, ip)).collect()
+    }
+
+    fn interface_rank(&self, name: &str) -> Option<usize> {
+        if self.interfaces.is_empty() {
+            return match VIRTUAL_INTERFACES.iter().any(|p| wildcard_match(p, name)) {
+                true => None,
+                false => Some(0),
+            };
+        }
+        self.interfaces.iter().position(|p| wildcard_match(p, name))
+    }
+
+    fn cidr_rank(&self, ip: &IpAddr) -> Option<usize> {
+        if self.cidrs.is_empty() { return Some(0); }
+        self.cidrs.iter().position(|c| c.contains(ip))
+    }
+}
+
+/// 网段 , 如 `10.0.0.0/8`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cidr {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    pub fn parse(cidr: &str) -> Result<Self, NacosError> {
+        let invalid = || NacosError::new(NacosErrorKind::Validate, &format!("bad cidr {}", cidr));
+        let (network, prefix) = cidr.trim().split_once('/').ok_or_else(invalid)?;
+        let network: IpAddr = network.parse().map_err(|_| invalid())?;
+        let prefix: u8 = prefix.parse().map_err(|_| invalid())?;
+        let max = if network.is_ipv4() { 32 } else { 128 };
+        if prefix > max { return Err(invalid()); }
+        Ok(Self { network, prefix })
+    }
+
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        match (self.network, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(network) & mask == u32::from(*ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(network) & mask == u128::from(*ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// `*` 匹配任意长度的字符
+pub(crate) fn wildcard_match(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((head, tail)) => match name.strip_prefix(head) {
+            Some(rest) => (0..=rest.len())
+                .filter(|i| rest.is_char_boundary(*i))
+                .any(|i| wildcard_match(tail, &rest[i..])),
+            None => false,
+        },
+    }
+}
+
+fn list_var(key: &str) -> Vec<String> {
+    env::var(key).unwrap_or_default()
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cidr(cidr: &str) -> Cidr {
+        Cidr::parse(cidr).unwrap()
+    }
+
+    fn ip(ip: &str) -> IpAddr {
+        ip.parse().unwrap()
+    }
+
+    #[test]
+    fn cidr_contains() {
+        assert!(cidr("10.0.0.0/8").contains(&ip("10.255.1.2")));
+        assert!(!cidr("10.0.0.0/8").contains(&ip("11.0.0.1")));
+        assert!(cidr("192.168.1.7/24").contains(&ip("192.168.1.200")));
+        assert!(!cidr("192.168.1.0/24").contains(&ip("192.168.2.1")));
+        assert!(cidr("0.0.0.0/0").contains(&ip("8.8.8.8")));
+        assert!(cidr("10.0.0.1/32").contains(&ip("10.0.0.1")));
+        assert!(!cidr("10.0.0.1/32").contains(&ip("10.0.0.2")));
+        assert!(cidr("fd00::/8").contains(&ip("fd12:3456::1")));
+        assert!(!cidr("fd00::/8").contains(&ip("fe80::1")));
+        assert!(cidr("::/0").contains(&ip("2001:db8::1")));
+        // 不同协议族不匹配
+        assert!(!cidr("0.0.0.0/0").contains(&ip("::1")));
+    }
+
+    #[test]
+    fn cidr_parse_errors() {
+        for bad in ["10.0.0.0", "10.0.0.0/33", "fd00::/129", "10.0.0/8", "10.0.0.0/x"] {
+            assert!(Cidr::parse(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn client_ip_must_be_valid() {
+        assert_eq!(None, parse_client_ip(None).unwrap());
+        assert_eq!(None, parse_client_ip(Some(" ".to_string())).unwrap());
+        assert_eq!(Some(ip("10.0.0.3")), parse_client_ip(Some(" 10.0.0.3 ".to_string())).unwrap());
+        let err = parse_client_ip(Some("10.0.0.300".to_string())).unwrap_err();
+        assert_eq!(NacosErrorKind::Validate, err.kind());
+        // 直接指定的地址优先于其他规则
+        let selector = IpSelector::new().allow_cidr("192.0.2.0/24").unwrap().ip(ip("10.0.0.3"));
+        assert_eq!(Some(ip("10.0.0.3")), selector.select());
+    }
+
+    #[test]
+    fn wildcard() {
+        assert!(wildcard_match("eth0", "eth0"));
+        assert!(!wildcard_match("eth0", "eth01"));
+        assert!(wildcard_match("eth*", "eth0"));
+        assert!(wildcard_match("eth*", "eth"));
+        assert!(!wildcard_match("eth*", "en0"));
+        assert!(wildcard_match("*", ""));
+        assert!(wildcard_match("*0", "eth0"));
+        assert!(wildcard_match("br-*-*", "br-a-b"));
+        assert!(!wildcard_match("br-*-*", "br-ab"));
+        assert!(wildcard_match("网卡*", "网卡一"));
+    }
+}
@@ -0,0 +1,278 @@
+use serde::de::{self, DeserializeOwned, Deserializer, IntoDeserializer, Visitor};
+use serde::de::value::{Error, MapDeserializer, SeqDeserializer};
+use serde::forward_to_deserialize_any;
+use std::collections::BTreeMap;
+
+/// 解析 .properties 内容为有序的键值对 , 支持 `#` `!` 注释 , `=` `:` 分隔与行尾 `\` 续行
+pub fn parse(content: &str) -> Vec<(String, String)> {
+    let mut pairs = Vec::new();
+    let mut logical = String::new();
+    for line in content.lines() {
+        let line = if logical.is_empty() { line.trim() } else { line.trim_start() };
+        if logical.is_empty() && (line.is_empty() || line.starts_with('#') || line.starts_with('!')) {
+            continue;
+        }
+        let trailing = line.chars().rev().take_while(|c| *c == '\\').count();
+        if trailing % 2 == 1 {
+            logical.push_str(&line[..line.len() - 1]);
+            continue;
+        }
+        logical.push_str(line);
+        pairs.push(split_pair(&logical));
+        logical.clear();
+    }
+    if !logical.is_empty() { pairs.push(split_pair(&logical)); }
+    pairs
+}
+
+fn split_pair(line: &str) -> (String, String) {
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '=' | ':' | ' ' | '\t' => {
+                let value = line[i + 1..].trim_start();
+                let value = if c.is_whitespace() {
+                    value.strip_prefix(|c| c == '=' || c == ':').unwrap_or(value).trim_start()
+                } else { value };
+                return (unescape(line[..i].trim_end()), unescape(value));
+            }
+            _ => {}
+        }
+    }
+    (unescape(line), String::new())
+}
+
+fn unescape(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => result.push('\t'),
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some('f') => result.push('\u{c}'),
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                    Some(u) => result.push(u),
+                    None => {
+                        result.push_str("\\u");
+                        result.push_str(&hex);
+                    }
+                }
+            }
+            Some(other) => result.push(other),
+            None => {}
+        }
+    }
+    result
+}
+
+/// 将 .properties 内容反序列化为 [T] , 以 `.` 分割的键展开为嵌套结构 ,
+/// 值按目标字段类型解析 , 逗号分割的值可以反序列化为序列
+pub fn from_str<T: DeserializeOwned>(content: &str) -> Result<T, Error> {
+    T::deserialize(tree(parse(content))?)
+}
+
+/// 将合并后的 JSON 树反序列化为 [T] , 字符串值与 properties 的值一样按目标字段类型解析 ,
+/// 使 properties 与 yaml json 等格式的配置可以合并后一起反序列化
+pub(crate) fn from_value<T: DeserializeOwned>(value: serde_json::Value) -> Result<T, Error> {
+    T::deserialize(Node::from(value))
+}
+
+/// 展开后的 properties 节点
+pub(crate) enum Node {
+    Leaf(String),
+    Map(BTreeMap<String, Node>),
+    /// 来自其他格式的非字符串值 , 按其原本的类型反序列化
+    Value(serde_json::Value),
+}
+
+impl From<serde_json::Value> for Node {
+    fn from(value: serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::String(s) => Node::Leaf(s),
+            serde_json::Value::Object(map) => Node::Map(map.into_iter().map(|(k, v)| (k, Node::from(v))).collect()),
+            other => Node::Value(other),
+        }
+    }
+}
+
+pub(crate) fn tree(pairs: Vec<(String, String)>) -> Result<Node, Error> {
+    let mut root = BTreeMap::new();
+    for (key, value) in pairs {
+        let mut parts: Vec<&str> = key.split('.').collect();
+        let last = parts.pop().unwrap_or_default();
+        let mut current = &mut root;
+        for part in parts {
+            let node = current.entry(part.to_string()).or_insert_with(|| Node::Map(BTreeMap::new()));
+            current = match node {
+                Node::Map(map) => map,
+                _ => return Err(de::Error::custom(format!("conflicting property key `{}`", key))),
+            };
+        }
+        if let Some(Node::Map(_)) = current.get(last) {
+            return Err(de::Error::custom(format!("conflicting property key `{}`", key)));
+        }
+        current.insert(last.to_string(), Node::Leaf(value));
+    }
+    Ok(Node::Map(root))
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident : $ty:ty),* $(,)?) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            match self {
+                Node::Leaf(s) => visitor.$visit(s.trim().parse::<$ty>().map_err(de::Error::custom)?),
+                map => map.deserialize_any(visitor),
+            }
+        }
+    )*};
+}
+
+impl<'de> Deserializer<'de> for Node {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self {
+            Node::Leaf(s) => visitor.visit_string(s),
+            Node::Map(map) => visitor.visit_map(MapDeserializer::new(map.into_iter())),
+            Node::Value(v) => v.deserialize_any(visitor).map_err(de::Error::custom),
+        }
+    }
+
+    deserialize_parsed! {
+        deserialize_bool => visit_bool: bool,
+        deserialize_i8 => visit_i8: i8,
+        deserialize_i16 => visit_i16: i16,
+        deserialize_i32 => visit_i32: i32,
+        deserialize_i64 => visit_i64: i64,
+        deserialize_u8 => visit_u8: u8,
+        deserialize_u16 => visit_u16: u16,
+        deserialize_u32 => visit_u32: u32,
+        deserialize_u64 => visit_u64: u64,
+        deserialize_f32 => visit_f32: f32,
+        deserialize_f64 => visit_f64: f64,
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self {
+            Node::Value(serde_json::Value::Null) => visitor.visit_none(),
+            node => visitor.visit_some(node),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V)
+                                                   -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self {
+            Node::Leaf(s) => {
+                let items: Vec<Node> = s.split(',')
+                    .map(|item| item.trim())
+                    .filter(|item| !item.is_empty())
+                    .map(|item| Node::Leaf(item.to_string()))
+                    .collect();
+                visitor.visit_seq(SeqDeserializer::new(items.into_iter()))
+            }
+            // `list.0=a` `list.1=b` 形式 按下标排序
+            Node::Map(map) => {
+                let mut items: Vec<(usize, Node)> = map.into_iter()
+                    .map(|(k, v)| k.parse::<usize>().map(|i| (i, v)).map_err(de::Error::custom))
+                    .collect::<Result<_, Error>>()?;
+                items.sort_by_key(|(i, _)| *i);
+                visitor.visit_seq(SeqDeserializer::new(items.into_iter().map(|(_, v)| v)))
+            }
+            Node::Value(v) => v.deserialize_seq(visitor).map_err(de::Error::custom),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(self, name: &'static str, variants: &'static [&'static str], visitor: V)
+                                         -> Result<V::Value, Self::Error> {
+        match self {
+            Node::Leaf(s) => visitor.visit_enum(s.into_deserializer()),
+            Node::Map(_) => Err(de::Error::custom(format!("expect one of {:?} for enum {}", variants, name))),
+            Node::Value(v) => v.deserialize_enum(name, variants, visitor).map_err(de::Error::custom),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        char str string bytes byte_buf unit unit_struct tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for Node {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    fn pair(key: &str, value: &str) -> (String, String) {
+        (key.to_string(), value.to_string())
+    }
+
+    #[test]
+    fn parse_separators_and_comments() {
+        let content = "# comment\n! comment\n\na=1\nb: 2\nc 3\nd\t=\t4\ne : = 5\nempty\n  f = 6  ";
+        assert_eq!(vec![pair("a", "1"), pair("b", "2"), pair("c", "3"), pair("d", "4"),
+                        pair("e", "= 5"), pair("empty", ""), pair("f", "6")], parse(content));
+    }
+
+    #[test]
+    fn parse_escapes() {
+        let content = "key\\ with\\ space = a\\tb\\u0041\na\\=b\\:c = d\npath = c:\\\\\nbad = \\uZZZZ";
+        assert_eq!(vec![pair("key with space", "a\tbA"), pair("a=b:c", "d"),
+                        pair("path", "c:\\"), pair("bad", "\\uZZZZ")], parse(content));
+    }
+
+    #[test]
+    fn parse_continuations() {
+        let content = "list = a,\\\n    b,\\\n    c\nnext = 1\nlast = x\\";
+        assert_eq!(vec![pair("list", "a,b,c"), pair("next", "1"), pair("last", "x")], parse(content));
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Server {
+        port: u16,
+        hosts: Vec<String>,
+        tls: Tls,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Tls {
+        enabled: bool,
+    }
+
+    #[test]
+    fn nest_dotted_keys() {
+        let content = "port = 8080\nhosts = a, b\ntls.enabled = true";
+        let server: Server = from_str(content).unwrap();
+        assert_eq!(Server { port: 8080, hosts: vec!["a".to_string(), "b".to_string()], tls: Tls { enabled: true } }, server);
+
+        let indexed: Vec<String> = from_str::<BTreeMap<String, Vec<String>>>("hosts.1 = b\nhosts.0 = a")
+            .unwrap().remove("hosts").unwrap();
+        assert_eq!(vec!["a", "b"], indexed);
+    }
+
+    #[test]
+    fn reject_conflicting_keys() {
+        assert!(from_str::<serde_json::Value>("a = 1\na.b = 2").is_err());
+        assert!(from_str::<serde_json::Value>("a.b = 2\na = 1").is_err());
+    }
+}
@@ -0,0 +1,268 @@
+use crate::model::err::{NacosError, NacosErrorKind};
+use crate::util::CLIENT;
+use lazy_static::lazy_static;
+use reqwest::multipart::{Form, Part};
+use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 请求的默认超时
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+lazy_static! {
+    /// 未指定传输层时所有 [crate::NacosConfig] 共享的 reqwest 传输层
+    pub(crate) static ref DEFAULT_TRANSPORT: Arc<dyn HttpTransport> = Arc::new(ReqwestTransport::default());
+}
+
+/// [HttpTransport::send] 返回的 future
+pub type TransportFuture<'a> = Pin<Box<dyn Future<Output=Result<HttpResponse, NacosError>> + Send + 'a>>;
+
+/// 发送 nacos 请求的 HTTP 传输层 , 默认为 [ReqwestTransport] , 可以替换为其他 HTTP 实现或测试替身 ;
+/// 请求未能送达时返回 Request 错误 , 非 2xx 的响应由调用方处理
+/// the HTTP transport sending nacos requests , [ReqwestTransport] by default , which can be replaced by
+/// other HTTP stacks or test doubles . return a Request err if the request is not delivered ,
+/// the non-2xx responses are handled by the caller .
+/// ```rust,no_run
+/// use nacos_api::NacosConfig;
+/// use nacos_api::util::transport::{HttpRequest, HttpResponse, HttpTransport, TransportFuture};
+/// use std::sync::Arc;
+///
+/// struct AlwaysOk;
+///
+/// impl HttpTransport for AlwaysOk {
+///     fn send(&self, _request: HttpRequest) -> TransportFuture<'_> {
+///         Box::pin(async { Ok(HttpResponse::new(200, "ok")) })
+///     }
+/// }
+///
+/// let nacos = NacosConfig::new("http", "192.168.0.132", 8848).with_transport(Arc::new(AlwaysOk));
+/// ```
+pub trait HttpTransport: Send + Sync {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HttpMethod {
+    Get,
+    Post,
+    Put,
+    Delete,
+}
+
+/// 请求体
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum HttpBody {
+    Empty,
+    /// application/x-www-form-urlencoded
+    Form(Vec<(String, String)>),
+    Json(serde_json::Value),
+    /// 只包含一个文件的 multipart/form-data
+    File {
+        field: String,
+        file_name: String,
+        #[serde(with = "bytes")]
+        content: Vec<u8>,
+    },
+}
+
+/// 与 HTTP 实现无关的请求 , 查询参数按名称排序
+/// the request independent of HTTP stacks , the query params are sorted by name .
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HttpRequest {
+    pub method: HttpMethod,
+    pub url: String,
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+    pub body: HttpBody,
+    pub timeout: Option<Duration>,
+}
+
+impl HttpRequest {
+    /// 超时默认为 10 秒
+    pub fn new(method: HttpMethod, url: &str) -> Self {
+        Self {
+            method,
+            url: url.to_string(),
+            query: Vec::new(),
+            headers: Vec::new(),
+            body: HttpBody::Empty,
+            timeout: Some(DEFAULT_TIMEOUT),
+        }
+    }
+    pub fn get(url: &str) -> Self {
+        Self::new(HttpMethod::Get, url)
+    }
+    pub fn post(url: &str) -> Self {
+        Self::new(HttpMethod::Post, url)
+    }
+    pub fn put(url: &str) -> Self {
+        Self::new(HttpMethod::Put, url)
+    }
+    pub fn delete(url: &str) -> Self {
+        Self::new(HttpMethod::Delete, url)
+    }
+
+    pub fn query(mut self, params: &HashMap<String, String>) -> Self {
+        self.query.extend(params.iter().map(|(k, v)| (k.clone(), v.clone())));
+        self.query.sort();
+        self
+    }
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+    pub fn form(mut self, params: &HashMap<String, String>) -> Self {
+        let mut form: Vec<_> = params.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        form.sort();
+        self.body = HttpBody::Form(form);
+        self
+    }
+    pub fn json(mut self, value: serde_json::Value) -> Self {
+        self.body = HttpBody::Json(value);
+        self
+    }
+    pub fn file(mut self, field: &str, file_name: &str, content: Vec<u8>) -> Self {
+        self.body = HttpBody::File { field: field.to_string(), file_name: file_name.to_string(), content };
+        self
+    }
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+/// 与 HTTP 实现无关的响应 , 响应头名称为小写
+/// the response independent of HTTP stacks , the header names are lowercase .
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    #[serde(with = "bytes")]
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self { status, headers: Vec::new(), body: body.into() }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_ascii_lowercase(), value.to_string()));
+        self
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// 忽略大小写地获取响应头
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+    }
+
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, NacosError> {
+        serde_json::from_slice(&self.body).map_err(|e| {
+            let reason = format!("bad response {} : {}", e, self.text());
+            NacosError::new(NacosErrorKind::Server, &reason)
+        })
+    }
+}
+
+/// 基于 reqwest 的传输层
+/// the transport based on reqwest .
+#[derive(Clone)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    /// 使用自定义的 reqwest 客户端 , 如配置代理或 TLS
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+impl Default for ReqwestTransport {
+    fn default() -> Self {
+        Self::new(CLIENT.clone())
+    }
+}
+
+impl Debug for ReqwestTransport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ReqwestTransport")
+    }
+}
+
+impl HttpTransport for ReqwestTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let method = match request.method {
+                HttpMethod::Get => reqwest::Method::GET,
+                HttpMethod::Post => reqwest::Method::POST,
+                HttpMethod::Put => reqwest::Method::PUT,
+                HttpMethod::Delete => reqwest::Method::DELETE,
+            };
+            let mut builder = self.client.request(method, &request.url).query(&request.query);
+            for (name, value) in request.headers.iter() {
+                builder = builder.header(name.as_str(), value.as_str());
+            }
+            builder = match request.body {
+                HttpBody::Empty => builder,
+                HttpBody::Form(form) => builder.form(&form),
+                HttpBody::Json(value) => builder.json(&value),
+                HttpBody::File { field, file_name, content } =>
+                    builder.multipart(Form::new().part(field, Part::bytes(content).file_name(file_name))),
+            };
+            if let Some(timeout) = request.timeout {
+                builder = builder.timeout(timeout);
+            }
+            let resp = builder.send().await.map_err(request_error)?;
+            let status = resp.status().as_u16();
+            let headers = resp.headers().iter()
+                .filter_map(|(k, v)| v.to_str().ok().map(|v| (k.as_str().to_string(), v.to_string())))
+                .collect();
+            let body = resp.bytes().await.map_err(request_error)?.to_vec();
+            Ok(HttpResponse { status, headers, body })
+        })
+    }
+}
+
+fn request_error(e: reqwest::Error) -> NacosError {
+    NacosError::new(NacosErrorKind::Request, &e.to_string())
+}
+
+/// 字节内容为 utf8 时序列化为字符串 , 否则为 `{"base64": "..."}`
+mod bytes {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(untagged)]
+    enum Bytes {
+        Text(String),
+        Base64 { base64: String },
+    }
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => Bytes::Text(text.to_string()),
+            Err(_) => Bytes::Base64 { base64: base64::encode(bytes) },
+        }.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        match Bytes::deserialize(deserializer)? {
+            Bytes::Text(text) => Ok(text.into_bytes()),
+            Bytes::Base64 { base64 } => base64::decode(base64).map_err(serde::de::Error::custom),
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use nacos_api::{DeployConfig, ServerConfig};
+use nacos_api::blocking::{NacosClient, NacosConfigApi, NacosConfigClient};
+use nacos_api::model::service_dto::SearchMode;
+use nacos_api::testing::MockNacos;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::runtime::Runtime;
+
+/// 模拟服务端运行在独立的运行时上 , 测试线程只使用同步接口
+fn start_mock() -> (Runtime, MockNacos) {
+    let runtime = Runtime::new().unwrap();
+    let nacos = runtime.block_on(MockNacos::start()).unwrap();
+    (runtime, nacos)
+}
+
+/// 轮询 [check] 直到为 true , 最多等待 5 秒
+fn eventually(check: impl Fn() -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !check() {
+        assert!(Instant::now() < deadline, "condition not met in 5s");
+        thread::sleep(Duration::from_millis(20));
+    }
+}
+
+#[derive(Deserialize)]
+struct Orders {
+    max_items: u32,
+}
+
+#[test]
+fn read_publish_and_watch_configs() {
+    let (_runtime, nacos) = start_mock();
+    let config = nacos.nacos_config();
+    let api = NacosConfigApi::new(DeployConfig::new("orders.yaml", "DEFAULT_GROUP", None));
+    api.publish_configs(&config, "max_items: 10", Some("yaml".to_string()), None).unwrap();
+
+    let client = NacosConfigClient::from(api);
+    assert_eq!(10, client.get_as::<Orders>(&config).unwrap().max_items);
+    let mut changes = client.watch(&config);
+    assert_eq!("max_items: 10", changes.recv_timeout(Duration::from_secs(5)).unwrap().new_content);
+    nacos.set_config("orders.yaml", "DEFAULT_GROUP", None, "max_items: 20", Some("yaml"));
+    assert_eq!("max_items: 20", changes.recv_timeout(Duration::from_secs(5)).unwrap().new_content);
+
+    let orders = client.live::<Orders>(&config).unwrap();
+    nacos.set_config("orders.yaml", "DEFAULT_GROUP", None, "max_items: 30", Some("yaml"));
+    eventually(|| orders.load().max_items == 30);
+
+    let found: Vec<_> = NacosConfigApi::search_all(&config, SearchMode::Blur, 1, None)
+        .map(|c| c.unwrap().data_id)
+        .collect();
+    assert_eq!(vec!["orders.yaml"], found);
+}
+
+#[test]
+fn register_beat_and_deregister() {
+    let (_runtime, nacos) = start_mock();
+    nacos.set_beat_interval(50);
+    let client = NacosClient::new(&nacos.nacos_config(), ServerConfig::new("127.0.0.1", 8080, "orders", None, None));
+    let registration = client.try_register(None).unwrap();
+    assert_eq!(1, nacos.instances("orders").len());
+    // 心跳在共享运行时上继续发送
+    eventually(|| nacos.beat_count("orders", "127.0.0.1", 8080) >= 2);
+    assert!(registration.current_status().is_alive());
+    let mut status = registration.status();
+    let changed = status.wait_change_timeout(Duration::from_secs(5)).unwrap();
+    assert!(changed.last_success.is_some());
+    assert_eq!("http://127.0.0.1:8080", client.get_addr_simple("orders").unwrap());
+
+    registration.deregister().unwrap();
+    assert!(nacos.instances("orders").is_empty());
+}
+
+#[test]
+fn report_health_of_persistent_instance() {
+    let (_runtime, nacos) = start_mock();
+    let mut server = ServerConfig::new("127.0.0.1", 8080, "orders", None, None);
+    server.set_ephemeral(false);
+    let client = NacosClient::new(&nacos.nacos_config(), server);
+    let registration = client.try_register(None).unwrap();
+    // 持久化实例没有心跳任务 , 状态不再改变
+    assert!(registration.status().wait_change().is_none());
+    assert!(registration.current_status().registered);
+
+    let healthy = Arc::new(AtomicBool::new(true));
+    let check = healthy.clone();
+    let task = client.report_health_in_background(move || check.load(Ordering::SeqCst), 1);
+    healthy.store(false, Ordering::SeqCst);
+    eventually(|| !nacos.instances("orders")[0].healthy);
+    assert!(!task.is_finished());
+    task.abort();
+    eventually(|| task.is_finished());
+}
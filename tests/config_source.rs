@@ -0,0 +1,52 @@
+use config::Config;
+use nacos_api::NacosConfigClient;
+use nacos_api::integration::config_source::NacosSource;
+use nacos_api::testing::MockNacos;
+
+#[tokio::test]
+async fn stack_yaml_and_properties_sources() {
+    let nacos = MockNacos::start().await.unwrap();
+    // dataId 没有扩展名 , 格式来自 nacos 的 type
+    nacos.set_config("orders", "DEFAULT_GROUP", None, "max_items: 10\ndb:\n  host: a\n  port: 3306", Some("yaml"));
+    nacos.set_config("orders-prod", "DEFAULT_GROUP", None, "db.port=3307\nlevel=warn", Some("properties"));
+    let nacos_config = nacos.nacos_config();
+    let base = NacosSource::new(&nacos_config, NacosConfigClient::new("orders", "DEFAULT_GROUP", None));
+    let profile = NacosSource::new(&nacos_config, NacosConfigClient::new("orders-prod", "DEFAULT_GROUP", None));
+
+    let settings = Config::builder()
+        .add_async_source(base.clone())
+        .add_async_source(profile)
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(10, settings.get::<u32>("max_items").unwrap());
+    assert_eq!("a", settings.get::<String>("db.host").unwrap());
+    // properties 的字符串值按读取的类型转换
+    assert_eq!(3307, settings.get::<u16>("db.port").unwrap());
+    assert_eq!("warn", settings.get::<String>("level").unwrap());
+
+    let snapshot = base.snapshot().await.unwrap();
+    assert_eq!("nacos:DEFAULT_GROUP/orders", snapshot.origin());
+    let settings = Config::builder().add_source(snapshot).build().unwrap();
+    assert_eq!(3306, settings.get::<u16>("db.port").unwrap());
+}
+
+#[tokio::test]
+async fn missing_config_is_empty_unless_required() {
+    let nacos = MockNacos::start().await.unwrap();
+    nacos.set_config("orders.yaml", "DEFAULT_GROUP", None, "max_items: 10", Some("yaml"));
+    let nacos_config = nacos.nacos_config();
+    let absent = NacosSource::new(&nacos_config, NacosConfigClient::new("absent.yaml", "DEFAULT_GROUP", None));
+
+    let settings = Config::builder()
+        .add_async_source(NacosSource::new(&nacos_config, NacosConfigClient::new("orders.yaml", "DEFAULT_GROUP", None)))
+        .add_async_source(absent.clone().required(false))
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(10, settings.get::<u32>("max_items").unwrap());
+    assert!(absent.clone().required(false).snapshot().await.is_ok());
+
+    assert!(Config::builder().add_async_source(absent.clone()).build().await.is_err());
+    assert!(absent.snapshot().await.is_err());
+}
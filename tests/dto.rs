@@ -0,0 +1,19 @@
+#[test]
+fn dto_derive() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/dto_pass.rs");
+    t.compile_fail("tests/ui/dto_fail_*.rs");
+}
+
+#[test]
+fn builder_derive() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/builder_pass.rs");
+}
+
+#[test]
+fn nacos_config_derive() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/nacos_config_pass.rs");
+    t.compile_fail("tests/ui/nacos_config_fail_*.rs");
+}
@@ -0,0 +1,279 @@
+use nacos_api::{ConfigWatch, DeployConfig, LiveConfig, NacosClient, NacosConfigApi, NacosConfigClient, NacosServiceApi, ServerConfig};
+use nacos_api::api::filter::AesConfigFilter;
+use nacos_api::integration::binding::NacosConfig;
+use nacos_api::model::config_vo::ConfigChange;
+use nacos_api::model::err::{NacosError, NacosErrorKind};
+use nacos_api::model::service_dto::{PutServiceOption, RegisterInstanceOption, SearchMode, SearchConfigsOption};
+use nacos_api::testing::MockNacos;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time;
+
+/// 轮询 [check] 直到为 true , 最多等待 5 秒
+async fn eventually<F, Fut>(mut check: F)
+    where F: FnMut() -> Fut,
+          Fut: Future<Output=bool>
+{
+    time::timeout(Duration::from_secs(5), async {
+        while !check().await {
+            time::sleep(Duration::from_millis(20)).await;
+        }
+    }).await.expect("condition not met in 5s");
+}
+
+fn server(nacos: &MockNacos, name: &str, port: u16) -> NacosClient {
+    NacosClient::new(&nacos.nacos_config(), ServerConfig::new("127.0.0.1", port, name, None, None))
+}
+
+fn persistent_server(nacos: &MockNacos, name: &str, port: u16) -> NacosClient {
+    let mut server = ServerConfig::new("127.0.0.1", port, name, None, None);
+    server.set_ephemeral(false);
+    NacosClient::new(&nacos.nacos_config(), server)
+}
+
+#[tokio::test]
+async fn register_beat_and_deregister() {
+    let nacos = MockNacos::start().await.unwrap();
+    nacos.set_beat_interval(50);
+    let mut metadata = HashMap::new();
+    metadata.insert("version".to_string(), "1.0.0".to_string());
+    let option = RegisterInstanceOption::builder().weight(2.0).cluster("az1").metadata(metadata);
+    let registration = server(&nacos, "orders", 8080).try_register(option).await.unwrap();
+
+    let hosts = nacos.instances("orders");
+    assert_eq!(1, hosts.len());
+    assert_eq!(2.0, hosts[0].weight);
+    assert_eq!("az1", hosts[0].cluster_name);
+    assert_eq!(Some(&"1.0.0".to_string()), hosts[0].metadata.get("version"));
+
+    eventually(|| async { nacos.beat_count("orders", "127.0.0.1", 8080) >= 2 }).await;
+    assert!(registration.current_status().is_alive());
+
+    let view = NacosServiceApi::get_instance_list(&nacos.nacos_config(), "orders", None).await.unwrap();
+    assert_eq!(1, view.hosts.unwrap().len());
+
+    registration.deregister().await.unwrap();
+    assert!(nacos.instances("orders").is_empty());
+}
+
+#[tokio::test]
+async fn register_detects_empty_ip() {
+    let nacos = MockNacos::start().await.unwrap();
+    let client = NacosClient::new(&nacos.nacos_config(), ServerConfig::new("", 8080, "orders", None, None));
+    // 本文件中只有该测试读取 NACOS_CLIENT_IP
+    std::env::set_var("NACOS_CLIENT_IP", "10.0.0.300");
+    let err = client.try_register(None).await.err().unwrap();
+    assert_eq!(NacosErrorKind::Validate, err.kind());
+    assert_eq!(NacosErrorKind::Validate, client.register_in_background(None, 1).err().unwrap().kind());
+    assert!(nacos.instances("orders").is_empty());
+
+    std::env::set_var("NACOS_CLIENT_IP", "127.0.0.7");
+    let registration = client.try_register(None).await.unwrap();
+    std::env::remove_var("NACOS_CLIENT_IP");
+    assert_eq!("127.0.0.7", nacos.instances("orders")[0].ip);
+    eventually(|| async { nacos.beat_count("orders", "127.0.0.7", 8080) >= 1 }).await;
+    registration.deregister().await.unwrap();
+    assert!(nacos.instances("orders").is_empty());
+}
+
+#[tokio::test]
+async fn re_register_after_server_forgets() {
+    let nacos = MockNacos::start().await.unwrap();
+    nacos.set_beat_interval(50);
+    let _registration = server(&nacos, "orders", 8080).try_register(None).await.unwrap();
+    nacos.clear_instances();
+    eventually(|| async { nacos.instances("orders").len() == 1 }).await;
+}
+
+#[tokio::test]
+async fn register_batch_with_own_options() {
+    let nacos = MockNacos::start().await.unwrap();
+    nacos.set_beat_interval(50);
+    let servers = vec![
+        (ServerConfig::new("127.0.0.1", 8080, "orders", None, None), None),
+        (ServerConfig::new("127.0.0.1", 9090, "orders", None, None),
+         RegisterInstanceOption::builder().weight(2.0).into()),
+    ];
+    let registration = NacosClient::try_register_batch(&nacos.nacos_config(), servers).await.unwrap();
+    let weights: Vec<f64> = nacos.instances("orders").iter().map(|host| host.weight).collect();
+    assert_eq!(vec![1.0, 2.0], weights);
+    eventually(|| async {
+        nacos.beat_count("orders", "127.0.0.1", 8080) >= 2 && nacos.beat_count("orders", "127.0.0.1", 9090) >= 2
+    }).await;
+
+    registration.deregister().await.unwrap();
+    assert!(nacos.instances("orders").is_empty());
+}
+
+#[tokio::test]
+async fn reject_health_report_of_ephemeral_instance() {
+    let nacos = MockNacos::start().await.unwrap();
+    let client = server(&nacos, "orders", 8080);
+    let _registration = client.try_register(None).await.unwrap();
+    assert_eq!(Some(true), nacos.instances("orders")[0].ephemeral);
+    assert!(client.report_health(false).await.is_err());
+}
+
+#[tokio::test]
+async fn report_health_and_healthy_only() {
+    let nacos = MockNacos::start().await.unwrap();
+    let client = persistent_server(&nacos, "orders", 8080);
+    client.register(None).await;
+    persistent_server(&nacos, "orders", 8081).register(None).await;
+    assert!(nacos.instances("orders").iter().all(|host| host.ephemeral == Some(false)));
+    client.report_health(false).await.unwrap();
+    // 持久化实例不发送心跳
+    assert_eq!(0, nacos.beat_count("orders", "127.0.0.1", 8080));
+
+    let addr = client.get_addr_simple("orders").await.unwrap();
+    assert_eq!("http://127.0.0.1:8081", addr);
+}
+
+#[tokio::test]
+async fn manage_services() {
+    let nacos = MockNacos::start().await.unwrap();
+    let config = nacos.nacos_config();
+    NacosServiceApi::create_server(&config, "orders", None).await.unwrap();
+    assert!(NacosServiceApi::create_server(&config, "orders", None).await.is_err());
+    NacosServiceApi::create_server(&config, "payments", None).await.unwrap();
+
+    NacosServiceApi::update_server(&config, "orders", PutServiceOption::builder().protect_threshold(0.5)).await.unwrap();
+    let info = NacosServiceApi::get_server(&config, "orders", None).await.unwrap();
+    assert_eq!(0.5, info.protect_threshold);
+
+    let list = NacosServiceApi::get_server_list(&config, 1, 10, None).await.unwrap();
+    assert_eq!(vec!["orders", "payments"], list.doms);
+
+    NacosServiceApi::delete_server(&config, "orders", None).await.unwrap();
+    assert!(NacosServiceApi::get_server(&config, "orders", None).await.is_err());
+}
+
+#[tokio::test]
+async fn publish_get_and_compare_and_swap() {
+    let nacos = MockNacos::start().await.unwrap();
+    let config = nacos.nacos_config();
+    let api = NacosConfigApi::new(DeployConfig::new("orders.yaml", "DEFAULT_GROUP", None));
+    let err = api.get_configs_content(&config).await.err().unwrap();
+    assert_eq!(Some(NacosErrorKind::NotFound), err.downcast_ref::<NacosError>().map(|e| e.kind()));
+
+    api.publish_configs(&config, "max_items: 10", Some("yaml".to_string()), None).await.unwrap();
+    let content = api.get_configs_content(&config).await.unwrap();
+    assert_eq!("max_items: 10", content.content);
+    assert_eq!(Some("yaml".to_string()), content.config_type);
+
+    let err = api.publish_configs_cas(&config, "max_items: 20", None, "stale", None).await.err().unwrap();
+    assert_eq!(Some(NacosErrorKind::Conflict), err.downcast_ref::<NacosError>().map(|e| e.kind()));
+
+    let written = api.modify_configs(&config, Some("yaml".to_string()), 3, |current| current.replace("10", "30"))
+        .await.unwrap();
+    assert_eq!("max_items: 30", written);
+    assert_eq!(Some(written), nacos.config("orders.yaml", "DEFAULT_GROUP", None));
+
+    NacosConfigApi::delete_configs(&config, DeployConfig::new("orders.yaml", "DEFAULT_GROUP", None)).await.unwrap();
+    assert_eq!(None, nacos.config("orders.yaml", "DEFAULT_GROUP", None));
+}
+
+#[tokio::test]
+async fn search_configs() {
+    let nacos = MockNacos::start().await.unwrap();
+    nacos.set_config("orders.yaml", "DEFAULT_GROUP", None, "a: 1", Some("yaml"));
+    nacos.set_config("orders-db.yaml", "DEFAULT_GROUP", None, "b: 2", Some("yaml"));
+    nacos.set_config("payments.yaml", "DEFAULT_GROUP", None, "c: 3", Some("yaml"));
+    nacos.set_config("orders.yaml", "DEFAULT_GROUP", Some("dev"), "d: 4", Some("yaml"));
+
+    let option = SearchConfigsOption::builder().data_id("orders*");
+    let page = NacosConfigApi::search(&nacos.nacos_config(), SearchMode::Blur, 1, 10, option).await.unwrap();
+    let data_ids: Vec<_> = page.page_items.iter().map(|c| c.data_id.as_str()).collect();
+    assert_eq!(vec!["orders.yaml", "orders-db.yaml"], data_ids);
+
+    let option = SearchConfigsOption::builder().data_id("orders.yaml").tenant("dev");
+    let page = NacosConfigApi::search(&nacos.nacos_config(), SearchMode::Accurate, 1, 10, option).await.unwrap();
+    assert_eq!(1, page.total_count);
+    assert_eq!(Some("d: 4".to_string()), page.page_items[0].content);
+}
+
+async fn recv(changes: &mut ConfigWatch) -> ConfigChange {
+    time::timeout(Duration::from_secs(5), changes.recv()).await.unwrap().unwrap()
+}
+
+#[tokio::test]
+async fn watch_config_changes() {
+    let nacos = MockNacos::start().await.unwrap();
+    nacos.set_config("orders.yaml", "DEFAULT_GROUP", None, "max_items: 10", Some("yaml"));
+    let client = NacosConfigClient::new("orders.yaml", "DEFAULT_GROUP", None);
+    let mut changes = client.watch(&nacos.nacos_config());
+
+    assert_eq!("max_items: 10", recv(&mut changes).await.new_content);
+    nacos.set_config("orders.yaml", "DEFAULT_GROUP", None, "max_items: 20", Some("yaml"));
+    let change = recv(&mut changes).await;
+    assert_eq!("max_items: 10", change.old_content);
+    assert_eq!("max_items: 20", change.new_content);
+    nacos.remove_config("orders.yaml", "DEFAULT_GROUP", None);
+    assert_eq!("", recv(&mut changes).await.new_content);
+}
+
+#[tokio::test]
+async fn full_change_stream_keeps_the_latest() {
+    let nacos = MockNacos::start().await.unwrap();
+    nacos.set_config("orders.yaml", "DEFAULT_GROUP", None, "max_items: 0", Some("yaml"));
+    let client = NacosConfigClient::new("orders.yaml", "DEFAULT_GROUP", None);
+    let mut changes = client.watch(&nacos.nacos_config());
+    let latest = changes.latest();
+
+    // 不读取变更流 , 写入超过缓存容量的变更
+    for i in 1..=70 {
+        let content = format!("max_items: {}", i);
+        nacos.set_config("orders.yaml", "DEFAULT_GROUP", None, &content, Some("yaml"));
+        let (latest, content) = (&latest, &content);
+        eventually(|| async move { latest.borrow().eq(content) }).await;
+    }
+    let mut received = Vec::new();
+    while let Ok(Some(change)) = time::timeout(Duration::from_millis(200), changes.recv()).await {
+        received.push(change.new_content);
+    }
+    assert_eq!(64, received.len());
+    assert_eq!("max_items: 7", received[0]);
+    assert_eq!("max_items: 70", received[63]);
+}
+
+#[derive(Deserialize, NacosConfig)]
+#[nacos(data_id = "orders.yaml")]
+struct Orders {
+    max_items: u32,
+}
+
+#[tokio::test]
+async fn live_binding_refreshes() {
+    let nacos = MockNacos::start().await.unwrap();
+    nacos.set_config("orders.yaml", "DEFAULT_GROUP", None, "max_items: 10", Some("yaml"));
+    let orders = LiveConfig::<Orders>::bind(&nacos.nacos_config()).await.unwrap();
+    assert_eq!(10, orders.load().max_items);
+
+    nacos.set_config("orders.yaml", "DEFAULT_GROUP", None, "max_items: 20", Some("yaml"));
+    eventually(|| async { orders.load().max_items == 20 }).await;
+    // 解析失败时保留当前值
+    nacos.set_config("orders.yaml", "DEFAULT_GROUP", None, "max_items: many", Some("yaml"));
+    time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(20, orders.load().max_items);
+}
+
+#[tokio::test]
+async fn encrypt_cipher_configs() {
+    let nacos = MockNacos::start().await.unwrap();
+    let config = nacos.nacos_config();
+    let deploy = DeployConfig::new("cipher-aes-db.yaml", "DEFAULT_GROUP", None);
+    let err = NacosConfigApi::upload_configs(&config, deploy.clone(), "password: secret", None).await.err().unwrap();
+    assert_eq!(Some(NacosErrorKind::Validate), err.downcast_ref::<NacosError>().map(|e| e.kind()));
+    let err = NacosConfigApi::upload_configs_cas(&config, deploy.clone(), "password: secret", None, "", None).await.err().unwrap();
+    assert_eq!(Some(NacosErrorKind::Validate), err.downcast_ref::<NacosError>().map(|e| e.kind()));
+
+    let api = NacosConfigApi::new(deploy.clone())
+        .with_filter(Arc::new(AesConfigFilter::new("0123456789abcdef").unwrap()));
+    api.publish_configs(&config, "password: secret", Some("yaml".to_string()), None).await.unwrap();
+    let stored = NacosConfigApi::new(deploy).get_configs(&config).await.unwrap();
+    assert_ne!("password: secret", stored);
+    assert_eq!("password: secret", api.get_configs(&config).await.unwrap());
+}
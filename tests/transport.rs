@@ -0,0 +1,197 @@
+use nacos_api::{DeployConfig, NacosClient, NacosConfig, NacosConfigApi, NacosServiceApi, ServerConfig};
+use nacos_api::model::err::{NacosError, NacosErrorKind};
+use nacos_api::model::service_dto::{PostConfigsOption, SearchMode};
+use nacos_api::testing::{MockNacos, RecordingTransport, ReplayTransport};
+use nacos_api::util::transport::{HttpMethod, HttpRequest, HttpResponse, HttpTransport, TransportFuture};
+use std::collections::HashMap;
+use std::future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+fn kind(err: &(dyn std::error::Error + 'static)) -> Option<NacosErrorKind> {
+    err.downcast_ref::<NacosError>().map(|e| e.kind())
+}
+
+#[tokio::test]
+async fn record_then_replay_offline() {
+    let nacos = MockNacos::start().await.unwrap();
+    let recording = Arc::new(RecordingTransport::new(nacos.nacos_config().transport().clone()));
+    let config = nacos.nacos_config().with_transport(recording.clone());
+    let api = NacosConfigApi::new(DeployConfig::new("orders.yaml", "DEFAULT_GROUP", None));
+    api.publish_configs(&config, "max_items: 10", Some("yaml".to_string()), None).await.unwrap();
+    api.get_configs_content(&config).await.unwrap();
+    assert_eq!(2, recording.exchanges().len());
+
+    let path = std::env::temp_dir().join(format!("nacos_api_replay_{}.json", std::process::id()));
+    recording.save(&path).unwrap();
+    let replay = ReplayTransport::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    drop(nacos);
+
+    let config = config.with_transport(Arc::new(replay));
+    let content = api.get_configs_content(&config).await.unwrap();
+    assert_eq!("max_items: 10", content.content);
+    assert_eq!(Some("yaml".to_string()), content.config_type);
+
+    let other = NacosConfigApi::new(DeployConfig::new("payments.yaml", "DEFAULT_GROUP", None));
+    let err = other.get_configs(&config).await.err().unwrap();
+    assert_eq!(Some(NacosErrorKind::NotFound), kind(err.as_ref()));
+}
+
+#[tokio::test]
+async fn hand_written_beat_fake() {
+    let nacos = NacosConfig::new("http", "127.0.0.1", 8848);
+    let server = ServerConfig::new("127.0.0.1", 8080, "orders", None, None);
+    let mut query = HashMap::new();
+    query.insert("ip".to_string(), "127.0.0.1".to_string());
+    query.insert("port".to_string(), "8080".to_string());
+    query.insert("serviceName".to_string(), "orders".to_string());
+    query.insert("ephemeral".to_string(), "true".to_string());
+    let beat = HttpRequest::put(&nacos.addr("/v1/ns/instance/beat")).query(&query);
+    let forgotten = r#"{"clientBeatInterval":5000,"code":20404,"lightBeatEnabled":true}"#;
+    let known = r#"{"clientBeatInterval":5000,"code":10200,"lightBeatEnabled":true}"#;
+    let replay = Arc::new(ReplayTransport::new(Vec::new())
+        .on(beat.clone(), HttpResponse::new(200, forgotten))
+        .on(HttpRequest::post(&nacos.addr("/v1/ns/instance")).query(&query), HttpResponse::new(200, "ok"))
+        .on(beat, HttpResponse::new(200, known)));
+    let nacos = nacos.with_transport(replay.clone());
+
+    let api = NacosServiceApi::new(server);
+    assert_eq!(20404, api.hart_beat(&nacos).await.unwrap().code);
+    api.register_instance(&nacos, None).await.unwrap();
+    assert_eq!(10200, api.hart_beat(&nacos).await.unwrap().code);
+    // 匹配项用尽后重复最后一个
+    assert_eq!(10200, api.hart_beat(&nacos).await.unwrap().code);
+
+    let err = api.remove_instance(&nacos, None).await.err().unwrap();
+    assert_eq!(Some(NacosErrorKind::NotFound), kind(err.as_ref()));
+    let methods: Vec<_> = replay.requests().iter().map(|r| r.method).collect();
+    assert_eq!(vec![HttpMethod::Put, HttpMethod::Post, HttpMethod::Put, HttpMethod::Put, HttpMethod::Delete], methods);
+}
+
+#[tokio::test]
+async fn rollback_refuses_history_without_content() {
+    let nacos = NacosConfig::new("http", "127.0.0.1", 8848);
+    let deploy = DeployConfig::new("orders.yaml", "DEFAULT_GROUP", None);
+    let history = |nid: &str| {
+        let mut query = deploy.init_map();
+        query.insert("nid".to_string(), nid.to_string());
+        HttpRequest::get(&nacos.addr("/v1/cs/history")).query(&query)
+    };
+    let empty = r#"{"id":"1","dataId":"orders.yaml","group":"DEFAULT_GROUP","opType":"D "}"#;
+    let replay = Arc::new(ReplayTransport::new(Vec::new())
+        .on(history("1"), HttpResponse::new(200, empty))
+        .on(history("2"), HttpResponse::new(404, "")));
+    let config = nacos.clone().with_transport(replay.clone());
+    let api = NacosConfigApi::new(deploy.clone());
+
+    let err = api.rollback_to(&config, 1).await.err().unwrap();
+    assert_eq!(Some(NacosErrorKind::NotFound), kind(err.as_ref()));
+    let err = api.get_history_detail(&config, 2).await.err().unwrap();
+    assert_eq!(Some(NacosErrorKind::NotFound), kind(err.as_ref()));
+    // 没有重新发布空内容
+    assert!(replay.requests().iter().all(|r| r.method == HttpMethod::Get));
+}
+
+#[tokio::test]
+async fn search_reports_server_errors() {
+    let nacos = NacosConfig::new("http", "127.0.0.1", 8848);
+    let mut query = HashMap::new();
+    query.insert("search".to_string(), "accurate".to_string());
+    query.insert("pageNo".to_string(), "1".to_string());
+    query.insert("pageSize".to_string(), "10".to_string());
+    query.insert("dataId".to_string(), String::new());
+    query.insert("group".to_string(), String::new());
+    let replay = Arc::new(ReplayTransport::new(Vec::new())
+        .on(HttpRequest::get(&nacos.addr("/v1/cs/configs")).query(&query), HttpResponse::new(403, "no permission")));
+    let nacos = nacos.with_transport(replay);
+
+    let err = NacosConfigApi::search(&nacos, SearchMode::Accurate, 1, 10, None).await.err().unwrap();
+    assert_eq!(Some(NacosErrorKind::Server), kind(err.as_ref()));
+    assert!(err.to_string().contains("no permission"));
+}
+
+#[tokio::test]
+async fn publish_get_and_stop_beta() {
+    let nacos = NacosConfig::new("http", "127.0.0.1", 8848);
+    let deploy = DeployConfig::new("orders.yaml", "DEFAULT_GROUP", None);
+    let mut publish = deploy.init_map();
+    publish.insert("content".to_string(), "max_items: 20".to_string());
+    publish.insert("type".to_string(), "yaml".to_string());
+    publish.insert("appName".to_string(), "orders".to_string());
+    let mut beta = deploy.init_map();
+    beta.insert("beta".to_string(), "true".to_string());
+    let info = r#"{"code":200,"message":null,"data":{"id":"7","dataId":"orders.yaml","group":"DEFAULT_GROUP","content":"max_items: 20","betaIps":"10.0.0.1,10.0.0.2"}}"#;
+    let replay = Arc::new(ReplayTransport::new(Vec::new())
+        .on(HttpRequest::post(&nacos.addr("/v1/cs/configs")).query(&publish), HttpResponse::new(200, "true"))
+        .on(HttpRequest::get(&nacos.addr("/v1/cs/configs")).query(&beta), HttpResponse::new(200, info))
+        .on(HttpRequest::delete(&nacos.addr("/v1/cs/configs")).query(&beta), HttpResponse::new(200, r#"{"code":200,"data":true}"#)));
+    let config = nacos.with_transport(replay.clone());
+    let api = NacosConfigApi::new(deploy.clone());
+
+    let ips = vec!["10.0.0.1".to_string(), " 10.0.0.2 ".to_string()];
+    let option = PostConfigsOption::builder().app_name("orders");
+    api.publish_configs_beta(&config, "max_items: 20", Some("yaml".to_string()), &ips, option).await.unwrap();
+    let info = api.get_configs_beta(&config).await.unwrap().unwrap();
+    assert_eq!(Some("10.0.0.1,10.0.0.2".to_string()), info.beta_ips);
+    NacosConfigApi::delete_configs_beta(&config, deploy).await.unwrap();
+
+    let requests = replay.requests();
+    let header = requests[0].headers.iter().find(|(name, _)| name == "betaIps").map(|(_, ips)| ips.as_str());
+    assert_eq!(Some("10.0.0.1,10.0.0.2"), header);
+    assert!(requests[1..].iter().all(|r| r.query.contains(&("beta".to_string(), "true".to_string()))));
+    assert_eq!(vec![HttpMethod::Post, HttpMethod::Get, HttpMethod::Delete], requests.iter().map(|r| r.method).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn refuse_beta_without_ips() {
+    let replay = Arc::new(ReplayTransport::new(Vec::new()));
+    let config = NacosConfig::new("http", "127.0.0.1", 8848).with_transport(replay.clone());
+    let api = NacosConfigApi::new(DeployConfig::new("orders.yaml", "DEFAULT_GROUP", None));
+
+    let err = api.publish_configs_beta(&config, "max_items: 20", None, &[], None).await.err().unwrap();
+    assert_eq!(Some(NacosErrorKind::Validate), kind(err.as_ref()));
+    let blank = vec![" ".to_string(), String::new()];
+    let err = api.publish_configs_beta(&config, "max_items: 20", None, &blank, None).await.err().unwrap();
+    assert_eq!(Some(NacosErrorKind::Validate), kind(err.as_ref()));
+    // 没有以正式发布的方式发送
+    assert!(replay.requests().is_empty());
+}
+
+/// 发往 [port] 的心跳永远不返回 , 其他请求交给 [inner]
+struct HangingBeats {
+    inner: Arc<dyn HttpTransport>,
+    port: String,
+}
+
+impl HttpTransport for HangingBeats {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        let hang = request.url.ends_with("/instance/beat")
+            && request.query.iter().any(|(k, v)| k == "port" && v == &self.port);
+        match hang {
+            true => Box::pin(future::pending()),
+            false => self.inner.send(request),
+        }
+    }
+}
+
+#[tokio::test]
+async fn hung_beat_does_not_stall_other_instances() {
+    let nacos = MockNacos::start().await.unwrap();
+    nacos.set_beat_interval(50);
+    let hanging = Arc::new(HangingBeats { inner: nacos.nacos_config().transport().clone(), port: "9090".to_string() });
+    let config = nacos.nacos_config().with_transport(hanging);
+    let servers = vec![
+        (ServerConfig::new("127.0.0.1", 8080, "orders", None, None), None),
+        (ServerConfig::new("127.0.0.1", 9090, "orders", None, None), None),
+    ];
+    let registration = NacosClient::try_register_batch(&config, servers).await.unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while nacos.beat_count("orders", "127.0.0.1", 8080) < 3 {
+        assert!(Instant::now() < deadline, "beats of 8080 stalled by 9090");
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(0, nacos.beat_count("orders", "127.0.0.1", 9090));
+    assert!(registration.statuses()[1].borrow().consecutive_failures > 0);
+}
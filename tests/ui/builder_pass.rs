@@ -0,0 +1,32 @@
+use nacos_api::model::Builder;
+
+#[derive(Debug, Default, PartialEq, Builder)]
+pub struct Options {
+    /// 集群名
+    #[builder(rename = "cluster")]
+    cluster_name: Option<String>,
+    weight: Option<f64>,
+    page_no: i32,
+    r#type: Option<String>,
+}
+
+fn take(option: impl Into<Option<Options>>) -> Option<Options> {
+    option.into()
+}
+
+fn main() {
+    let options = Options::builder()
+        .cluster("az1")
+        .weight(2.0)
+        .page_no(3)
+        .r#type("yaml")
+        .build();
+    assert_eq!(options, Options {
+        cluster_name: Some("az1".to_string()),
+        weight: Some(2.0),
+        page_no: 3,
+        r#type: Some("yaml".to_string()),
+    });
+    assert_eq!(take(Options::builder().page_no(1)), Some(Options { page_no: 1, ..Default::default() }));
+    assert_eq!(take(None), None);
+}
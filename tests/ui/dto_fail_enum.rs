@@ -0,0 +1,9 @@
+use nacos_api::model::Dto;
+
+#[derive(Dto)]
+enum Mode {
+    Accurate,
+    Blur,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use nacos_api::model::Dto;
+
+#[derive(Default, Dto)]
+struct Paging {
+    page_no: Option<i32>,
+}
+
+#[derive(Dto)]
+struct Query {
+    #[dto(flatten, rename = "paging")]
+    paging: Paging,
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use nacos_api::model::Dto;
+
+#[derive(Dto)]
+struct Pair(Option<String>, Option<String>);
+
+fn main() {}
@@ -0,0 +1,9 @@
+use nacos_api::model::Dto;
+
+#[derive(Dto)]
+struct Query {
+    #[dto(default)]
+    data_id: Option<String>,
+}
+
+fn main() {}
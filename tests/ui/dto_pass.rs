@@ -0,0 +1,56 @@
+use nacos_api::model::Dto;
+use std::collections::HashMap;
+
+#[derive(Default, Dto)]
+struct Paging {
+    page_no: i32,
+    page_size: Option<i32>,
+}
+
+#[derive(Default, Dto)]
+struct Query {
+    data_id: String,
+    #[dto(rename = "src_user")]
+    src_user: Option<String>,
+    #[dto(skip)]
+    local_only: Option<String>,
+    #[dto(flatten)]
+    paging: Paging,
+    #[dto(flatten)]
+    extra: Option<Paging>,
+    #[dto(with = "join")]
+    tags: Option<Vec<String>>,
+    #[dto(with = "yes_no")]
+    enabled: bool,
+    r#type: Option<String>,
+}
+
+fn join(tags: &[String]) -> String {
+    tags.join(",")
+}
+
+fn yes_no(b: &bool) -> String {
+    if *b { "Y".to_string() } else { "N".to_string() }
+}
+
+fn main() {
+    let query = Query {
+        data_id: "orders.yaml".to_string(),
+        src_user: Some("deploy".to_string()),
+        local_only: Some("never sent".to_string()),
+        paging: Paging { page_no: 1, page_size: None },
+        tags: Some(vec!["a".to_string(), "b".to_string()]),
+        enabled: true,
+        r#type: Some("yaml".to_string()),
+        ..Default::default()
+    };
+    let mut map = HashMap::new();
+    query.mapping(&mut map);
+
+    let mut expected = HashMap::new();
+    for (k, v) in [("dataId", "orders.yaml"), ("src_user", "deploy"), ("pageNo", "1"),
+                   ("tags", "a,b"), ("enabled", "Y"), ("type", "yaml")] {
+        expected.insert(k.to_string(), v.to_string());
+    }
+    assert_eq!(map, expected);
+}
@@ -0,0 +1,10 @@
+use nacos_api::integration::binding::NacosConfig;
+use serde::Deserialize;
+
+#[derive(Deserialize, NacosConfig)]
+#[nacos(data_id = "orders.xml", format = "xml")]
+struct Orders {
+    max_items: u32,
+}
+
+fn main() {}
@@ -0,0 +1,36 @@
+use nacos_api::{ConfigFormat, LiveConfig, NacosConfig};
+use nacos_api::integration::binding::{ConfigBinding, NacosConfig, NacosConfigBinding};
+use serde::Deserialize;
+
+#[derive(Deserialize, NacosConfig)]
+#[nacos(data_id = "orders.yaml", group = "ORDERS", format = "yaml", refresh = false)]
+struct Orders {
+    #[allow(dead_code)]
+    max_items: u32,
+}
+
+#[derive(Deserialize, NacosConfig)]
+#[nacos(data_id = "redis.properties", tenant = "dev")]
+struct Redis {
+    #[allow(dead_code)]
+    host: String,
+}
+
+fn main() {
+    assert_eq!(Orders::binding(), ConfigBinding {
+        data_id: "orders.yaml",
+        group: "ORDERS",
+        tenant: None,
+        format: Some(ConfigFormat::Yaml),
+        refresh: false,
+    });
+    assert_eq!(Redis::binding(), ConfigBinding {
+        data_id: "redis.properties",
+        group: "DEFAULT_GROUP",
+        tenant: Some("dev"),
+        format: None,
+        refresh: true,
+    });
+    let nacos = NacosConfig::new("http", "127.0.0.1", 8848);
+    let _ = LiveConfig::<Orders>::bind(&nacos);
+}